@@ -0,0 +1,218 @@
+use crate::PotionIngredient::{
+    BlazePowder, Custom, FermentedSpiderEye, GhastTear, MagmaCream, SpiderEye, Sugar,
+};
+use crate::{LiquidData, PotionIngredient};
+use core::cmp::Ordering;
+use core::fmt;
+
+/// Represents one interaction with a cauldron
+///
+/// With the `serde` feature, serializing an action that adds a custom ingredient fails, see
+/// `PotionIngredient::Custom`. Recipes store them by letter instead.
+#[derive(Eq, PartialEq, Copy, Clone, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Action {
+    AddIngredient(PotionIngredient),
+    Dilute,
+    AddNetherWart,
+}
+
+/// Actions are ordered like their letters in `Action::ALL`, `SGEFBCWN`, which also orders recipes.
+/// Custom ingredients come after those, ordered by their letters.
+impl Ord for Action {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Action::AddIngredient(Custom(a)), Action::AddIngredient(Custom(b))) => a.cmp(b),
+            _ => self.index().cmp(&other.index()),
+        }
+    }
+}
+
+impl PartialOrd for Action {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Action {
+    /// Every action that can be taken on a cauldron in the game, so without custom ingredients.
+    pub const ALL: [Action; 8] = [
+        Action::AddIngredient(Sugar),
+        Action::AddIngredient(GhastTear),
+        Action::AddIngredient(SpiderEye),
+        Action::AddIngredient(FermentedSpiderEye),
+        Action::AddIngredient(BlazePowder),
+        Action::AddIngredient(MagmaCream),
+        Action::Dilute,
+        Action::AddNetherWart,
+    ];
+
+    /// Calculates the result of taking this action.
+    pub const fn apply_to(self, ld: LiquidData) -> LiquidData {
+        match self {
+            Action::AddIngredient(ing) => ld.apply_ingredient(ing),
+            Action::Dilute => ld.dilute(),
+            Action::AddNetherWart => ld.apply_wart(),
+        }
+    }
+
+    /// The letter used for this action in recipes, e.g. 'W' for diluting with water.
+    pub fn letter(self) -> char {
+        match self {
+            Action::AddIngredient(Sugar) => 'S',
+            Action::AddIngredient(GhastTear) => 'G',
+            Action::AddIngredient(SpiderEye) => 'E',
+            Action::AddIngredient(FermentedSpiderEye) => 'F',
+            Action::AddIngredient(BlazePowder) => 'B',
+            Action::AddIngredient(MagmaCream) => 'C',
+            Action::AddIngredient(Custom(ingredient)) => ingredient.letter(),
+            Action::Dilute => 'W',
+            Action::AddNetherWart => 'N',
+        }
+    }
+
+    /// The position of this action in `Action::ALL`, or its length for custom ingredients.
+    pub(crate) fn index(self) -> usize {
+        match self {
+            Action::AddIngredient(Sugar) => 0,
+            Action::AddIngredient(GhastTear) => 1,
+            Action::AddIngredient(SpiderEye) => 2,
+            Action::AddIngredient(FermentedSpiderEye) => 3,
+            Action::AddIngredient(BlazePowder) => 4,
+            Action::AddIngredient(MagmaCream) => 5,
+            Action::AddIngredient(Custom(_)) => Self::ALL.len(),
+            Action::Dilute => 6,
+            Action::AddNetherWart => 7,
+        }
+    }
+
+    /// Iterates over every liquid this action leaves as it is, in ascending order.
+    pub fn fixed_points(self) -> impl Iterator<Item = LiquidData> {
+        LiquidData::all().filter(move |ld| ld.is_stable_under(self))
+    }
+
+    /// Finds the action for a letter, see `letter`. Custom ingredients are looked up with
+    /// `IngredientRegistry::action` instead.
+    pub fn from_letter(letter: char) -> Option<Self> {
+        Self::ALL.iter().copied().find(|a| a.letter() == letter)
+    }
+}
+
+impl LiquidData {
+    /// Calculates the result of taking every action in order.
+    pub fn apply_all(self, actions: impl IntoIterator<Item = Action>) -> Self {
+        actions
+            .into_iter()
+            .fold(self, |ld, action| action.apply_to(ld))
+    }
+
+    /// Finds the action that turns `from` into `to`, e.g. to work out what a player did between two
+    /// looks at a cauldron. If several do, the first in `Action::ALL` is returned, and `None` if
+    /// no single action does.
+    pub fn transition_between(from: LiquidData, to: LiquidData) -> Option<Action> {
+        Action::ALL.iter().copied().find(|a| a.apply_to(from) == to)
+    }
+
+    /// Whether taking `action` leaves this liquid as it is, so that it would be a wasted step.
+    pub fn is_stable_under(self, action: Action) -> bool {
+        action.apply_to(self) == self
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.letter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Action, LiquidData, PotionIngredient};
+
+    #[test]
+    fn letters_round_trip() {
+        for action in Action::ALL.iter() {
+            assert_eq!(Action::from_letter(action.letter()), Some(*action));
+        }
+        assert_eq!(Action::from_letter('X'), None);
+    }
+
+    #[test]
+    fn index_matches_all() {
+        for (i, action) in Action::ALL.iter().enumerate() {
+            assert_eq!(action.index(), i);
+        }
+    }
+
+    #[test]
+    fn apply_all_applies_in_order() {
+        let actions = vec![
+            Action::Dilute,
+            Action::AddIngredient(PotionIngredient::SpiderEye),
+            Action::AddNetherWart,
+        ];
+        assert_eq!(LiquidData::default().apply_all(actions), LiquidData(1088));
+        assert_eq!(LiquidData(42).apply_all(vec![]), LiquidData(42));
+    }
+
+    #[test]
+    fn only_wart_can_change_a_liquid_twice() {
+        // whatever an ingredient or water brews is stable under it, so repeating them is pointless
+        for &action in Action::ALL[..7].iter() {
+            for ld in LiquidData::all() {
+                assert!(action.apply_to(ld).is_stable_under(action));
+            }
+        }
+        let wart = Action::AddNetherWart;
+        assert!(LiquidData::all().any(|ld| !wart.apply_to(ld).is_stable_under(wart)));
+    }
+
+    #[test]
+    fn dilution_fixes_liquids_without_diluted_bits() {
+        let expected = LiquidData::all().filter(|ld| ld.0 & LiquidData::DILUTED_BITS == 0);
+        assert!(Action::Dilute.fixed_points().eq(expected));
+        assert!(LiquidData::default().is_stable_under(Action::Dilute));
+        assert!(
+            !LiquidData::default().is_stable_under(Action::AddIngredient(PotionIngredient::Sugar))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn transitions_are_identified() {
+        use crate::fixtures::{self, MUNDANE, SPIDER_EYE, SPIDER_EYE_WART, STRENGTH, WATER};
+        for fixture in fixtures::ALL.iter() {
+            let from = fixture.liquid;
+            for &action in Action::ALL.iter() {
+                let to = action.apply_to(from);
+                let found = LiquidData::transition_between(from, to).unwrap();
+                assert_eq!(found.apply_to(from), to);
+                assert!(found <= action);
+            }
+        }
+        assert_eq!(
+            LiquidData::transition_between(SPIDER_EYE.liquid, SPIDER_EYE_WART.liquid),
+            Some(Action::AddNetherWart)
+        );
+        // both sugar and diluting leave it as it is
+        assert_eq!(
+            LiquidData::transition_between(MUNDANE.liquid, MUNDANE.liquid),
+            Some(Action::AddIngredient(PotionIngredient::Sugar))
+        );
+        assert_eq!(
+            LiquidData::transition_between(WATER.liquid, STRENGTH.liquid),
+            None
+        );
+    }
+
+    #[test]
+    fn apply_to_matches_liquid_data() {
+        let ld = LiquidData(31011);
+        assert_eq!(Action::AddNetherWart.apply_to(ld), ld.apply_wart());
+        assert_eq!(Action::Dilute.apply_to(ld), ld.dilute());
+        assert_eq!(
+            Action::AddIngredient(PotionIngredient::Sugar).apply_to(LiquidData(0)),
+            LiquidData(1)
+        );
+    }
+}
@@ -0,0 +1,104 @@
+//! `proptest` strategies for the core types, enabled by the `proptest` feature.
+
+use crate::{Action, LiquidData, PotionIngredient, Recipe};
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::sample::select;
+
+/// The longest recipe generated by default, longer than any shortest recipe from water.
+const MAX_RECIPE_LEN: usize = 24;
+
+impl Arbitrary for LiquidData {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    /// Generates any 15-bit value, which covers every liquid the game can produce.
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        (0u16..0x8000).prop_map(LiquidData).boxed()
+    }
+}
+
+impl Arbitrary for PotionIngredient {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        select(
+            &[
+                PotionIngredient::Sugar,
+                PotionIngredient::GhastTear,
+                PotionIngredient::SpiderEye,
+                PotionIngredient::FermentedSpiderEye,
+                PotionIngredient::BlazePowder,
+                PotionIngredient::MagmaCream,
+            ][..],
+        )
+        .boxed()
+    }
+}
+
+impl Arbitrary for Action {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    /// Generates the actions in `Action::ALL`, custom ingredients are left out.
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        select(&Action::ALL[..]).boxed()
+    }
+}
+
+impl Arbitrary for Recipe {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    /// Generates recipes of up to `MAX_RECIPE_LEN` actions.
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        vec(any::<Action>(), 0..=MAX_RECIPE_LEN)
+            .prop_map(Recipe)
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MAX_RECIPE_LEN;
+    use crate::{Action, LiquidData, PotionIngredient, Recipe};
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn ingredients_are_idempotent(ld: LiquidData, ingredient: PotionIngredient) {
+            let once = ld.apply_ingredient(ingredient);
+            prop_assert_eq!(once.apply_ingredient(ingredient), once);
+        }
+
+        #[test]
+        fn wart_stays_within_15_bits(ld: LiquidData) {
+            prop_assert!(ld.apply_wart().0 < 0x8000);
+        }
+
+        #[test]
+        fn actions_are_built_in(action: Action) {
+            prop_assert!(Action::ALL.contains(&action));
+        }
+
+        #[test]
+        fn recipes_round_trip(recipe: Recipe) {
+            prop_assert!(recipe.0.len() <= MAX_RECIPE_LEN);
+            prop_assert_eq!(recipe.to_string().parse::<Recipe>().unwrap(), recipe);
+        }
+
+        #[test]
+        fn recipes_apply_in_order(ld: LiquidData, recipe: Recipe) {
+            let folded = recipe.0.iter().fold(ld, |ld, a| a.apply_to(ld));
+            prop_assert_eq!(ld.apply_all(recipe.0), folded);
+        }
+
+        #[test]
+        fn simplified_recipes_brew_the_same(ld: LiquidData, recipe: Recipe) {
+            let simplified = recipe.simplify(ld);
+            prop_assert!(simplified.0.len() <= recipe.0.len());
+            prop_assert_eq!(simplified.apply(ld), recipe.apply(ld));
+        }
+    }
+}
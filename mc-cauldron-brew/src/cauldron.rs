@@ -0,0 +1,159 @@
+//! A cauldron with a fill level, which decides which actions can be taken.
+//!
+//! A full cauldron holds three bottles. Ingredients and nether wart need some liquid to go into,
+//! and a water bucket fills the cauldron back up, so there must be room for it. That is why a
+//! bottle has to be taken out before diluting.
+
+use crate::{Action, LiquidData, PotionIngredient};
+use core::fmt;
+
+/// How many bottles a full cauldron holds.
+pub const MAX_LEVEL: u8 = 3;
+
+/// A cauldron: what it holds and how many bottles of it.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct CauldronState {
+    pub liquid: LiquidData,
+    /// From 0 (empty) to `MAX_LEVEL`.
+    pub level: u8,
+}
+
+/// The reason an action can't be taken on a cauldron.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum CauldronError {
+    /// There is no liquid to add anything to or to fill a bottle from.
+    Empty,
+    /// There is no room for a water bucket.
+    Full,
+}
+
+impl CauldronState {
+    /// A cauldron that was just filled with a water bucket.
+    pub const fn full_of_water() -> Self {
+        CauldronState {
+            liquid: LiquidData(0),
+            level: MAX_LEVEL,
+        }
+    }
+
+    /// A cauldron without any liquid.
+    pub const fn empty() -> Self {
+        CauldronState {
+            liquid: LiquidData(0),
+            level: 0,
+        }
+    }
+
+    /// Whether there is no liquid left.
+    pub const fn is_empty(self) -> bool {
+        self.level == 0
+    }
+
+    /// Fills a bottle, which takes one level, and returns what it was filled with.
+    pub fn take_bottle(&mut self) -> Result<LiquidData, CauldronError> {
+        if self.is_empty() {
+            return Err(CauldronError::Empty);
+        }
+        self.level -= 1;
+        Ok(self.liquid)
+    }
+
+    /// Fills the cauldron up with a water bucket, which dilutes what is left in it.
+    ///
+    /// An empty cauldron is simply filled with water.
+    pub fn add_water_bucket(&mut self) -> Result<(), CauldronError> {
+        if self.level >= MAX_LEVEL {
+            return Err(CauldronError::Full);
+        }
+        *self = if self.is_empty() {
+            Self::full_of_water()
+        } else {
+            CauldronState {
+                liquid: self.liquid.dilute(),
+                level: MAX_LEVEL,
+            }
+        };
+        Ok(())
+    }
+
+    /// Adds an ingredient to the liquid, which leaves the level as it is.
+    pub fn add_ingredient(&mut self, ingredient: PotionIngredient) -> Result<(), CauldronError> {
+        if self.is_empty() {
+            return Err(CauldronError::Empty);
+        }
+        self.liquid = self.liquid.apply_ingredient(ingredient);
+        Ok(())
+    }
+
+    /// Adds a nether wart to the liquid, which leaves the level as it is.
+    pub fn add_wart(&mut self) -> Result<(), CauldronError> {
+        if self.is_empty() {
+            return Err(CauldronError::Empty);
+        }
+        self.liquid = self.liquid.apply_wart();
+        Ok(())
+    }
+
+    /// Takes an action, where `Action::Dilute` means adding a water bucket.
+    pub fn apply(&mut self, action: Action) -> Result<(), CauldronError> {
+        match action {
+            Action::AddIngredient(ingredient) => self.add_ingredient(ingredient),
+            Action::Dilute => self.add_water_bucket(),
+            Action::AddNetherWart => self.add_wart(),
+        }
+    }
+}
+
+impl Default for CauldronState {
+    fn default() -> Self {
+        Self::full_of_water()
+    }
+}
+
+impl fmt::Display for CauldronError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CauldronError::Empty => f.write_str("the cauldron is empty"),
+            CauldronError::Full => f.write_str("the cauldron is full"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CauldronError {}
+
+#[cfg(test)]
+mod tests {
+    use super::{CauldronError, CauldronState};
+    use crate::PotionIngredient::SpiderEye;
+    use crate::{Action, LiquidData};
+
+    #[test]
+    fn diluting_needs_a_bottle_taken_first() {
+        let mut cauldron = CauldronState::full_of_water();
+        cauldron.add_ingredient(SpiderEye).unwrap();
+        assert_eq!(cauldron.add_water_bucket(), Err(CauldronError::Full));
+        assert_eq!(cauldron.take_bottle(), Ok(LiquidData(1184)));
+        assert_eq!(cauldron.level, 2);
+        cauldron.add_water_bucket().unwrap();
+        assert_eq!(cauldron.liquid, LiquidData(1184).dilute());
+        assert_eq!(cauldron.level, 3);
+    }
+
+    #[test]
+    fn empty_cauldrons_only_take_water() {
+        let mut cauldron = CauldronState::full_of_water();
+        cauldron.apply(Action::AddNetherWart).unwrap();
+        for _ in 0..3 {
+            cauldron.take_bottle().unwrap();
+        }
+        assert_eq!(cauldron.take_bottle(), Err(CauldronError::Empty));
+        assert_eq!(
+            cauldron.add_ingredient(SpiderEye),
+            Err(CauldronError::Empty)
+        );
+        assert_eq!(cauldron.add_wart(), Err(CauldronError::Empty));
+        cauldron.add_water_bucket().unwrap();
+        assert_eq!(cauldron, CauldronState::full_of_water());
+    }
+}
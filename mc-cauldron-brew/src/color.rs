@@ -0,0 +1,91 @@
+//! The color of a potion's liquid, as the client renders it.
+
+use crate::{LiquidData, PotionEffect};
+
+/// The color of liquids without effects.
+pub const WATER_COLOR: u32 = 0x385dc6;
+
+impl PotionEffect {
+    /// The color this effect contributes to a potion, as `0xRRGGBB`.
+    pub fn liquid_color(self) -> u32 {
+        match self {
+            PotionEffect::Swiftness => 0x7cafc6,
+            PotionEffect::Slowness => 0x5a6c81,
+            PotionEffect::Haste => 0xd9c043,
+            PotionEffect::MiningFatigue => 0x4a4217,
+            PotionEffect::Strength => 0x932423,
+            PotionEffect::Healing => 0xf82423,
+            PotionEffect::Harming => 0x430a09,
+            PotionEffect::Leaping => 0x786297,
+            PotionEffect::Nausea => 0x551d4a,
+            PotionEffect::Regeneration => 0xcd5cab,
+            PotionEffect::Resistance => 0x99453a,
+            PotionEffect::FireResistance => 0xe49a3a,
+            PotionEffect::WaterBreathing => 0x2e5299,
+            PotionEffect::Invisibility => 0x7f8392,
+            PotionEffect::Blindness => 0x1f1f23,
+            PotionEffect::NightVision => 0x1f1fa1,
+            PotionEffect::Hunger => 0x587653,
+            PotionEffect::Weakness => 0x484d48,
+            PotionEffect::Poison => 0x4e9331,
+        }
+    }
+}
+
+impl LiquidData {
+    /// The color of the liquid, as `0xRRGGBB`.
+    ///
+    /// This is the average of the effects' colors, where each effect is counted once per tier.
+    /// Liquids without effects have the color of water.
+    pub fn color(self) -> u32 {
+        let effects = self.effect_details();
+        if effects.is_empty() {
+            return WATER_COLOR;
+        }
+
+        // The client sums the channels as floats, so the rounding matches only if we do as well.
+        let mut sum = [0f32; 3];
+        let mut count = 0f32;
+        for details in effects {
+            let color = details.effect.liquid_color();
+            for _ in 0..=details.amplifier {
+                for (channel, shift) in sum.iter_mut().zip([16, 8, 0].iter()) {
+                    *channel += (color >> shift & 0xff) as f32 / 255.0;
+                }
+                count += 1.0;
+            }
+        }
+        let [r, g, b] = sum.map(|channel| (channel / count * 255.0) as u32);
+        r << 16 | g << 8 | b
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::color::WATER_COLOR;
+    use crate::{LiquidData, PotionEffect};
+
+    #[test]
+    fn liquids_without_effects_look_like_water() {
+        assert_eq!(LiquidData(0).color(), WATER_COLOR);
+        assert_eq!(LiquidData(1).color(), WATER_COLOR);
+    }
+
+    #[test]
+    fn single_effect_has_its_own_color() {
+        assert_eq!(LiquidData(1 << 11).effects(), vec![PotionEffect::Healing]);
+        assert_eq!(LiquidData(1 << 11).color(), 0xf82423);
+    }
+
+    #[test]
+    fn colors_are_averaged() {
+        // Mining Fatigue and Poison II, so poison is counted twice
+        let ld = LiquidData(16896);
+        assert_eq!(
+            ld.effects(),
+            vec![PotionEffect::MiningFatigue, PotionEffect::Poison]
+        );
+        assert_eq!(ld.effect_details()[1].amplifier, 1);
+        assert_eq!(ld.color(), 0x4c7828);
+    }
+}
@@ -0,0 +1,87 @@
+//! Ingredients that aren't in the game, e.g. ones that a mod adds.
+//!
+//! A custom ingredient sets some bits of the liquid, like the built-in ones do, so it can be used
+//! wherever a `PotionIngredient` can: `LiquidData::apply_ingredient`, `Action::AddIngredient`,
+//! recipes and the solvers that take a list of actions, e.g. `RecipeTable::build_using`. With
+//! `std`, an `IngredientRegistry` gives them letters for recipes.
+
+/// An ingredient that is defined by the user, see `PotionIngredient::Custom`.
+///
+/// Two ingredients are the same if their letter, name and bits are.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct CustomIngredient {
+    letter: char,
+    name: &'static str,
+    added_bits: &'static [u8],
+}
+
+impl CustomIngredient {
+    /// Defines an ingredient that sets `added_bits`, written as `letter` in recipes.
+    ///
+    /// # Panics
+    ///
+    /// If any of the bits is above the 15 that liquids have, which is a compile error when the
+    /// ingredient is a `const`.
+    pub const fn new(name: &'static str, letter: char, added_bits: &'static [u8]) -> Self {
+        let mut i = 0;
+        while i < added_bits.len() {
+            assert!(added_bits[i] < 15, "liquids only have 15 bits");
+            i += 1;
+        }
+        CustomIngredient {
+            letter,
+            name,
+            added_bits,
+        }
+    }
+
+    /// The name of the item, e.g. "Pixie Dust".
+    pub const fn name(self) -> &'static str {
+        self.name
+    }
+
+    /// The letter used for this ingredient in recipes.
+    pub const fn letter(self) -> char {
+        self.letter
+    }
+
+    /// Lists the bits that are set by this ingredient.
+    pub const fn added_bits(self) -> &'static [u8] {
+        self.added_bits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CustomIngredient;
+    use crate::{Action, LiquidData, PotionIngredient};
+
+    const PIXIE_DUST: CustomIngredient = CustomIngredient::new("Pixie Dust", 'P', &[2, 12]);
+
+    #[test]
+    fn custom_ingredients_set_their_bits() {
+        let ingredient = PotionIngredient::Custom(PIXIE_DUST);
+        assert_eq!(
+            LiquidData(1).apply_ingredient(ingredient),
+            LiquidData(1 | 1 << 2 | 1 << 12)
+        );
+        let action = Action::AddIngredient(ingredient);
+        assert_eq!(action.letter(), 'P');
+        assert_eq!(action.to_string(), "P");
+        assert!(action > Action::AddNetherWart);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn custom_ingredients_have_preimages() {
+        let action = Action::AddIngredient(PotionIngredient::Custom(PIXIE_DUST));
+        assert_eq!(LiquidData(0).preimages(action), vec![]);
+        assert_eq!(LiquidData(0x1004).preimages(action).len(), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "liquids only have 15 bits")]
+    fn bits_beyond_the_liquid_are_rejected() {
+        CustomIngredient::new("Broken", 'X', &[15]);
+    }
+}
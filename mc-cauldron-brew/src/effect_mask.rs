@@ -0,0 +1,146 @@
+//! Sets of effects as bit masks, for matching liquids against the effects that are wanted.
+
+use crate::{LiquidData, PotionEffect};
+use std::iter::FromIterator;
+
+/// A set of effects, one bit per effect in the order of `PotionEffect::ALL`.
+///
+/// Unlike a `Vec<PotionEffect>`, it is `Copy` and the set operations take a single instruction,
+/// which matters when checking every liquid.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct EffectMask(u32);
+
+impl EffectMask {
+    /// The set without any effects.
+    pub const EMPTY: EffectMask = EffectMask(0);
+
+    /// The set with only `effect`.
+    pub const fn single(effect: PotionEffect) -> Self {
+        EffectMask(1 << effect as u32)
+    }
+
+    pub const fn contains(self, effect: PotionEffect) -> bool {
+        self.0 & Self::single(effect).0 != 0
+    }
+
+    pub fn insert(&mut self, effect: PotionEffect) {
+        self.0 |= Self::single(effect).0;
+    }
+
+    /// The effects in either set.
+    pub const fn union(self, other: Self) -> Self {
+        EffectMask(self.0 | other.0)
+    }
+
+    /// The effects in both sets.
+    pub const fn intersection(self, other: Self) -> Self {
+        EffectMask(self.0 & other.0)
+    }
+
+    /// The effects in this set but not in `other`.
+    pub const fn difference(self, other: Self) -> Self {
+        EffectMask(self.0 & !other.0)
+    }
+
+    /// Whether every effect in this set is also in `other`.
+    pub const fn is_subset(self, other: Self) -> bool {
+        self.0 & !other.0 == 0
+    }
+
+    /// Whether no effect is in both sets.
+    pub const fn is_disjoint(self, other: Self) -> bool {
+        self.0 & other.0 == 0
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The number of effects in the set.
+    pub const fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Iterates over the effects in the set, in the order of their ids.
+    pub fn iter(self) -> impl Iterator<Item = PotionEffect> {
+        PotionEffect::ALL
+            .iter()
+            .copied()
+            .filter(move |&effect| self.contains(effect))
+    }
+}
+
+impl FromIterator<PotionEffect> for EffectMask {
+    fn from_iter<I: IntoIterator<Item = PotionEffect>>(effects: I) -> Self {
+        let mut mask = EffectMask::EMPTY;
+        for effect in effects {
+            mask.insert(effect);
+        }
+        mask
+    }
+}
+
+impl From<&[PotionEffect]> for EffectMask {
+    fn from(effects: &[PotionEffect]) -> Self {
+        effects.iter().copied().collect()
+    }
+}
+
+impl LiquidData {
+    /// The effects a potion with this damage value applies, as a set.
+    pub fn effect_mask(self) -> EffectMask {
+        self.effects().into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EffectMask;
+    use crate::fixtures::{self, FERMENTED, STRENGTH, WATER};
+    use crate::PotionEffect::{self, *};
+
+    #[test]
+    fn masks_hold_every_effect() {
+        let all: EffectMask = PotionEffect::ALL.iter().copied().collect();
+        assert_eq!(all.len(), PotionEffect::ALL.len());
+        assert!(all.iter().eq(PotionEffect::ALL.iter().copied()));
+        for &effect in PotionEffect::ALL.iter() {
+            let single = EffectMask::single(effect);
+            assert!(single.contains(effect));
+            assert_eq!(single.len(), 1);
+            assert!(single.is_subset(all));
+        }
+        assert!(EffectMask::EMPTY.is_empty());
+        assert_eq!(EffectMask::default(), EffectMask::EMPTY);
+    }
+
+    #[test]
+    fn set_operations() {
+        let strength = STRENGTH.liquid.effect_mask();
+        let fermented = FERMENTED.liquid.effect_mask();
+        assert_eq!(strength, EffectMask::from(&[Strength, Hunger, Poison][..]));
+        assert_eq!(strength.intersection(fermented), EffectMask::single(Poison));
+        assert_eq!(
+            strength.union(fermented),
+            EffectMask::from(&[MiningFatigue, Strength, Hunger, Poison][..])
+        );
+        assert_eq!(
+            strength.difference(fermented),
+            EffectMask::from(&[Strength, Hunger][..])
+        );
+        assert!(EffectMask::single(Poison).is_subset(strength));
+        assert!(!strength.is_subset(fermented));
+        assert!(strength.is_disjoint(EffectMask::single(Swiftness)));
+        assert!(!strength.is_disjoint(fermented));
+        assert!(WATER.liquid.effect_mask().is_empty());
+    }
+
+    #[test]
+    fn masks_match_effect_lists() {
+        for fixture in fixtures::ALL.iter() {
+            let mask = fixture.liquid.effect_mask();
+            assert!(mask.iter().eq(fixture.effects.iter().copied()));
+            assert_eq!(mask, EffectMask::from(fixture.effects));
+        }
+    }
+}
@@ -0,0 +1,581 @@
+//! Decoding of the effects a liquid applies, following `PotionHelper` from b1.9-pre2.
+//!
+//! Every effect has a requirement expression over the bits of the liquid. The expression is
+//! evaluated to an integer and the effect applies if that integer is positive.
+
+use crate::LiquidData;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// A status effect that a potion can apply.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum PotionEffect {
+    Swiftness,
+    Slowness,
+    Haste,
+    MiningFatigue,
+    Strength,
+    Healing,
+    Harming,
+    Leaping,
+    Nausea,
+    Regeneration,
+    Resistance,
+    FireResistance,
+    WaterBreathing,
+    Invisibility,
+    Blindness,
+    NightVision,
+    Hunger,
+    Weakness,
+    Poison,
+}
+
+impl PotionEffect {
+    /// All effects, in the order of their ids (which is the order the game checks them in).
+    pub const ALL: [PotionEffect; 19] = [
+        PotionEffect::Swiftness,
+        PotionEffect::Slowness,
+        PotionEffect::Haste,
+        PotionEffect::MiningFatigue,
+        PotionEffect::Strength,
+        PotionEffect::Healing,
+        PotionEffect::Harming,
+        PotionEffect::Leaping,
+        PotionEffect::Nausea,
+        PotionEffect::Regeneration,
+        PotionEffect::Resistance,
+        PotionEffect::FireResistance,
+        PotionEffect::WaterBreathing,
+        PotionEffect::Invisibility,
+        PotionEffect::Blindness,
+        PotionEffect::NightVision,
+        PotionEffect::Hunger,
+        PotionEffect::Weakness,
+        PotionEffect::Poison,
+    ];
+
+    /// The numeric id the game uses for this effect.
+    pub fn id(self) -> u8 {
+        self as u8 + 1
+    }
+
+    /// The English name of the effect.
+    pub fn name(self) -> &'static str {
+        match self {
+            PotionEffect::Swiftness => "Swiftness",
+            PotionEffect::Slowness => "Slowness",
+            PotionEffect::Haste => "Haste",
+            PotionEffect::MiningFatigue => "Mining Fatigue",
+            PotionEffect::Strength => "Strength",
+            PotionEffect::Healing => "Healing",
+            PotionEffect::Harming => "Harming",
+            PotionEffect::Leaping => "Leaping",
+            PotionEffect::Nausea => "Nausea",
+            PotionEffect::Regeneration => "Regeneration",
+            PotionEffect::Resistance => "Resistance",
+            PotionEffect::FireResistance => "Fire Resistance",
+            PotionEffect::WaterBreathing => "Water Breathing",
+            PotionEffect::Invisibility => "Invisibility",
+            PotionEffect::Blindness => "Blindness",
+            PotionEffect::NightVision => "Night Vision",
+            PotionEffect::Hunger => "Hunger",
+            PotionEffect::Weakness => "Weakness",
+            PotionEffect::Poison => "Poison",
+        }
+    }
+
+    /// The expression that decides whether a liquid has this effect.
+    fn requirement(self) -> &'static str {
+        match self {
+            PotionEffect::Swiftness => "!10 & !4 & 5*2+0 & >1 | !7 & !4 & 5*2+0 & >1",
+            PotionEffect::Slowness => "10 & 7 & !4 & 7+5+1-0",
+            PotionEffect::Haste => "2 & 12+2+6-1-7 & <8",
+            PotionEffect::MiningFatigue => "!2 & !1*2-9 & 14-5",
+            PotionEffect::Strength => "9 & 3 & 9+4+5 & <11",
+            PotionEffect::Healing => "11 & <6",
+            PotionEffect::Harming => "!11 & 1 & 10 & !7",
+            PotionEffect::Leaping => "8 & 2+0 & <5",
+            PotionEffect::Nausea => "8*2-!7+4-11 & !2 | 13 & 11 & 2*3-1-5",
+            PotionEffect::Regeneration => "!14 & 13*3-!0-!5-8",
+            PotionEffect::Resistance => "10 & 4 & 10+5+6 & <9",
+            PotionEffect::FireResistance => "14 & !5 & 6-!1 & 14+13+12",
+            PotionEffect::WaterBreathing => "0+1+12 & !6 & 10 & !11 & !13",
+            PotionEffect::Invisibility => "2+5+13-0-4 & !7 & !1 & >5",
+            PotionEffect::Blindness => "9 & !1 & !5 & !3 & =3",
+            PotionEffect::NightVision => "8*2-!7 & 5 & !0 & >3",
+            PotionEffect::Hunger => ">4>6>8-3-8>2",
+            PotionEffect::Weakness => "=1>5>7>9+3-7-2-11 & !10 & =0",
+            PotionEffect::Poison => "12+9 & !13 & !0",
+        }
+    }
+
+    /// Whether the effect is applied all at once instead of over time.
+    pub fn is_instant(self) -> bool {
+        matches!(self, PotionEffect::Healing | PotionEffect::Harming)
+    }
+
+    /// The expression for the effect's amplifier, for effects that can be amplified.
+    fn amplifier(self) -> Option<&'static str> {
+        match self {
+            PotionEffect::Swiftness => Some("7+!3-!1"),
+            PotionEffect::Haste => Some("1+0-!11"),
+            PotionEffect::Strength => Some("2+7-!12"),
+            PotionEffect::Healing => Some("11+!0-!1-!14"),
+            PotionEffect::Harming => Some("!11-!14+!0-!1"),
+            PotionEffect::Resistance => Some("12-!2"),
+            PotionEffect::Poison => Some("14>5"),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PotionEffect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for PotionEffect {
+    type Err = ParseEffectError;
+
+    /// Parses the English name, ignoring case, spaces and underscores (e.g. "fire_resistance").
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        fn normalize(s: &str) -> String {
+            s.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect()
+        }
+        let key = normalize(s);
+        PotionEffect::ALL
+            .iter()
+            .copied()
+            .find(|effect| normalize(effect.name()) == key)
+            .ok_or_else(|| ParseEffectError {
+                name: s.to_string(),
+            })
+    }
+}
+
+/// The error returned when parsing a name that isn't an effect.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ParseEffectError {
+    pub name: String,
+}
+
+impl fmt::Display for ParseEffectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown effect '{}'", self.name)
+    }
+}
+
+impl Error for ParseEffectError {}
+
+impl LiquidData {
+    /// Lists the effects a potion with this damage value applies, in the order the game lists them.
+    pub fn effects(self) -> Vec<PotionEffect> {
+        PotionEffect::ALL
+            .iter()
+            .copied()
+            .filter(|effect| expression::evaluate(effect.requirement(), self.0) > 0)
+            .collect()
+    }
+
+    /// Lists the effects together with their amplifier and duration.
+    pub fn effect_details(self) -> Vec<EffectDetails> {
+        PotionEffect::ALL
+            .iter()
+            .copied()
+            .filter_map(|effect| {
+                let level = expression::evaluate(effect.requirement(), self.0);
+                if level <= 0 {
+                    return None;
+                }
+                let amplifier = effect
+                    .amplifier()
+                    .map(|expr| expression::evaluate(expr, self.0).max(0))
+                    .unwrap_or(0);
+                let duration = if effect.is_instant() {
+                    1
+                } else {
+                    // 3 minutes for the first level, 5 more for every further one
+                    (1200 * (level * 3 + (level - 1) * 2)) >> amplifier
+                };
+                Some(EffectDetails {
+                    effect,
+                    amplifier: amplifier as u8,
+                    duration: duration as u32,
+                })
+            })
+            .collect()
+    }
+}
+
+/// An effect together with its strength, as applied by drinking a potion.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct EffectDetails {
+    pub effect: PotionEffect,
+    /// 0 for the first tier (e.g. "Poison"), 1 for the second ("Poison II") and so on.
+    pub amplifier: u8,
+    /// The duration in ticks, 1 for instant effects.
+    pub duration: u32,
+}
+
+impl fmt::Display for EffectDetails {
+    /// Formats the effect the way its tooltip reads, e.g. "Poison II (1:30)".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const NUMERALS: [&str; 10] = ["I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX", "X"];
+        f.write_str(self.effect.name())?;
+        if self.amplifier > 0 {
+            match NUMERALS.get(self.amplifier as usize) {
+                Some(numeral) => write!(f, " {}", numeral)?,
+                None => write!(f, " {}", self.amplifier as u32 + 1)?,
+            }
+        }
+        if !self.effect.is_instant() {
+            let seconds = self.duration / 20;
+            write!(f, " ({}:{:02})", seconds / 60, seconds % 60)?;
+        }
+        Ok(())
+    }
+}
+
+/// Liquids that apply exactly the same effects, with the same strength and duration, so that
+/// drinking any of them has the same result.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct EffectClass {
+    pub effects: Vec<EffectDetails>,
+    /// The liquids in the class, in the order they were given.
+    pub liquids: Vec<LiquidData>,
+}
+
+/// Groups `liquids` into classes with the same effects, ordered by their first liquid.
+pub fn effect_classes(liquids: impl IntoIterator<Item = LiquidData>) -> Vec<EffectClass> {
+    let mut classes: Vec<EffectClass> = Vec::new();
+    let mut index: HashMap<Vec<EffectDetails>, usize> = HashMap::new();
+    for ld in liquids {
+        let effects = ld.effect_details();
+        match index.get(&effects) {
+            Some(&i) => classes[i].liquids.push(ld),
+            None => {
+                index.insert(effects.clone(), classes.len());
+                classes.push(EffectClass {
+                    effects,
+                    liquids: vec![ld],
+                });
+            }
+        }
+    }
+    classes
+}
+
+/// The expression language used by `PotionHelper`.
+///
+/// An expression is a sum of terms, which are combined with `&` (all parts positive, yields the
+/// largest) and `|` (the first positive part). A term is a bit index that counts as 1 if the bit
+/// is set, optionally prefixed by `!` (bit is unset), `-` (negated), or `=`, `<`, `>` (compares
+/// the number of set bits against the index instead), and optionally followed by `*n`.
+///
+/// This is a straight port, including its quirks: `|` and `&` split at their first occurrence
+/// and drop the character in front of them, and everything that isn't part of a term is ignored.
+pub(crate) mod expression {
+    #[derive(Copy, Clone)]
+    enum Comparison {
+        Equal,
+        Greater,
+        Less,
+    }
+
+    #[derive(Default)]
+    struct Term {
+        negate: bool,
+        subtract: bool,
+        in_multiplier: bool,
+        multiplier: Option<i32>,
+        comparison: Option<Comparison>,
+        index: Option<i32>,
+    }
+
+    impl Term {
+        fn value(&self, data: u16) -> i32 {
+            let index = self.index.unwrap_or(0);
+            let mut value = if self.negate {
+                !is_set(data, index) as i32
+            } else if let Some(comparison) = self.comparison {
+                let count = data.count_ones() as i32;
+                let matches = match comparison {
+                    Comparison::Equal => count == index,
+                    Comparison::Greater => count > index,
+                    Comparison::Less => count < index,
+                };
+                matches as i32
+            } else {
+                is_set(data, index) as i32
+            };
+            if let Some(multiplier) = self.multiplier {
+                value *= multiplier;
+            }
+            if self.subtract {
+                value = -value;
+            }
+            value
+        }
+    }
+
+    fn is_set(data: u16, index: i32) -> bool {
+        (data as i32) & (1 << (index & 0x1f)) != 0
+    }
+
+    /// Evaluates an expression for a liquid.
+    pub fn evaluate(expr: &str, data: u16) -> i32 {
+        evaluate_or(expr.as_bytes(), 0, expr.len(), data)
+    }
+
+    /// Evaluates `a | b | ...`, which is the first positive part.
+    fn evaluate_or(expr: &[u8], start: usize, end: usize, data: u16) -> i32 {
+        let parts = split(expr, b'|', start, end);
+        if parts.len() == 1 {
+            return evaluate_and(expr, start, end, data);
+        }
+        for &(start, end) in &parts {
+            let value = evaluate_and(expr, start, end, data);
+            if value > 0 {
+                return value;
+            }
+        }
+        0
+    }
+
+    /// Evaluates `a & b & ...`, which is the largest part if all of them are positive.
+    fn evaluate_and(expr: &[u8], start: usize, end: usize, data: u16) -> i32 {
+        let parts = split(expr, b'&', start, end);
+        if parts.len() == 1 {
+            return evaluate_sum(expr, start, end, data);
+        }
+        let mut max = 0;
+        for &(start, end) in &parts {
+            let value = evaluate_sum(expr, start, end, data);
+            if value <= 0 {
+                return 0;
+            }
+            max = max.max(value);
+        }
+        max
+    }
+
+    /// Splits `start..end` at every `c`, dropping `c` and the character in front of it.
+    fn split(expr: &[u8], c: u8, start: usize, end: usize) -> Vec<(usize, usize)> {
+        let mut parts = Vec::new();
+        let mut part_start = start;
+        while let Some(i) = find(expr, c, part_start, end) {
+            parts.push((part_start, i.saturating_sub(1)));
+            part_start = i + 1;
+        }
+        parts.push((part_start, end));
+        parts
+    }
+
+    /// Evaluates a sum of terms.
+    fn evaluate_sum(expr: &[u8], start: usize, end: usize, data: u16) -> i32 {
+        if start >= expr.len() || start >= end {
+            return 0;
+        }
+        let mut sum = 0;
+        let mut term = Term::default();
+        for &c in &expr[start..end] {
+            match c {
+                b'0'..=b'9' => {
+                    let digit = (c - b'0') as i32;
+                    if term.in_multiplier {
+                        term.multiplier = Some(digit);
+                    } else {
+                        term.index = Some(term.index.unwrap_or(0) * 10 + digit);
+                    }
+                    continue;
+                }
+                b'*' => {
+                    term.in_multiplier = true;
+                    continue;
+                }
+                _ => {}
+            }
+            if term.index.is_some() && b"!-=<>+".contains(&c) {
+                sum += term.value(data);
+                term = Term::default();
+            }
+            match c {
+                b'!' => term.negate = true,
+                b'-' => term.subtract = true,
+                b'=' => term.comparison = Some(Comparison::Equal),
+                b'>' => term.comparison = Some(Comparison::Greater),
+                b'<' => term.comparison = Some(Comparison::Less),
+                _ => {}
+            }
+        }
+        if term.index.is_some() {
+            sum += term.value(data);
+        }
+        sum
+    }
+
+    /// Finds the first occurrence of `c` at or after `start`, if it is before `end`.
+    fn find(expr: &[u8], c: u8, start: usize, end: usize) -> Option<usize> {
+        expr.get(start..)?
+            .iter()
+            .position(|&x| x == c)
+            .map(|i| i + start)
+            .filter(|&i| i < end)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::evaluate;
+
+        #[test]
+        fn terms_are_summed() {
+            assert_eq!(evaluate("0+1-2", 0b011), 2);
+            assert_eq!(evaluate("0+1-2", 0b111), 1);
+            assert_eq!(evaluate("!0-!1", 0b000), 0);
+            assert_eq!(evaluate("1*3+0", 0b011), 4);
+        }
+
+        #[test]
+        fn comparisons_count_bits() {
+            assert_eq!(evaluate("=2", 0b101), 1);
+            assert_eq!(evaluate(">2", 0b101), 0);
+            assert_eq!(evaluate("<3", 0b101), 1);
+            assert_eq!(evaluate(">4>6>8-3-8>2", 0b1_1111), 1);
+            assert_eq!(evaluate(">4>6>8-3-8>2", 0b1111), 0);
+        }
+
+        #[test]
+        fn and_takes_the_maximum() {
+            assert_eq!(evaluate("0 & 1*3", 0b11), 3);
+            assert_eq!(evaluate("0 & 1*3", 0b10), 0);
+        }
+
+        #[test]
+        fn and_rejects_negative_parts() {
+            // Used to evaluate to -2 in release builds when `&` and `|` were handled recursively.
+            assert_eq!(evaluate("!14 & 13*3-!0-!5-8", 0), 0);
+            assert_eq!(evaluate("!1 & -!0", 0), 0);
+        }
+
+        #[test]
+        fn or_takes_the_first_positive() {
+            assert_eq!(evaluate("0*2 | 1*3", 0b11), 2);
+            assert_eq!(evaluate("0*2 | 1*3", 0b10), 3);
+            assert_eq!(evaluate("-0 | -1", 0b11), 0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::PotionEffect::*;
+    use crate::PotionIngredient::{FermentedSpiderEye, GhastTear};
+    use crate::{effect_classes, EffectDetails, LiquidData, ParseEffectError, PotionEffect};
+
+    #[test]
+    fn water_has_no_effects() {
+        assert_eq!(LiquidData(0).effects(), vec![]);
+    }
+
+    #[test]
+    fn classes_share_effects() {
+        let classes = effect_classes(LiquidData::all());
+        assert_eq!(classes[0].liquids[0], LiquidData(0));
+        assert_eq!(classes[0].effects, vec![]);
+        assert_eq!(
+            classes.iter().map(|c| c.liquids.len()).sum::<usize>(),
+            0x8000
+        );
+        for class in &classes {
+            for &ld in &class.liquids {
+                assert_eq!(ld.effect_details(), class.effects);
+            }
+        }
+        assert!(classes
+            .windows(2)
+            .all(|w| w[0].liquids[0] < w[1].liquids[0]));
+    }
+
+    #[test]
+    fn water_ghast_tear_heals() {
+        assert_eq!(
+            LiquidData::default().apply_ingredient(GhastTear).effects(),
+            vec![Healing]
+        );
+    }
+
+    #[test]
+    fn water_fermented_is_poison_and_mining_fatigue() {
+        assert_eq!(
+            LiquidData::default()
+                .apply_ingredient(FermentedSpiderEye)
+                .effects(),
+            vec![MiningFatigue, Poison]
+        );
+    }
+
+    #[test]
+    fn details_include_amplifier_and_duration() {
+        let details = LiquidData::default()
+            .apply_ingredient(FermentedSpiderEye)
+            .effect_details();
+        assert_eq!(
+            details,
+            vec![
+                EffectDetails {
+                    effect: MiningFatigue,
+                    amplifier: 0,
+                    duration: 3600,
+                },
+                EffectDetails {
+                    effect: Poison,
+                    amplifier: 1,
+                    duration: 1800,
+                },
+            ]
+        );
+        assert_eq!(details[0].to_string(), "Mining Fatigue (3:00)");
+        assert_eq!(details[1].to_string(), "Poison II (1:30)");
+    }
+
+    #[test]
+    fn instant_effects_last_one_tick() {
+        let details = LiquidData::default()
+            .apply_ingredient(GhastTear)
+            .effect_details();
+        assert_eq!(details[0].duration, 1);
+        assert_eq!(details[0].to_string(), "Healing");
+    }
+
+    #[test]
+    fn names_parse_loosely() {
+        assert_eq!("Fire Resistance".parse(), Ok(FireResistance));
+        assert_eq!("fire_resistance".parse(), Ok(FireResistance));
+        assert_eq!("NIGHTVISION".parse(), Ok(NightVision));
+        for effect in PotionEffect::ALL.iter() {
+            assert_eq!(effect.to_string().parse(), Ok(*effect));
+        }
+        assert_eq!(
+            "Luck".parse::<PotionEffect>(),
+            Err(ParseEffectError {
+                name: "Luck".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn ids_match_the_game() {
+        assert_eq!(Swiftness.id(), 1);
+        assert_eq!(Poison.id(), 19);
+        for (i, effect) in PotionEffect::ALL.iter().enumerate() {
+            assert_eq!(effect.id() as usize, i + 1);
+        }
+    }
+}
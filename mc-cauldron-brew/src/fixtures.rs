@@ -0,0 +1,122 @@
+//! Liquids whose effects, names and shortest recipes are known, for checking code built on this
+//! crate, e.g. bindings, against the same data its own tests use.
+
+use crate::LiquidData;
+use crate::PotionEffect::{self, *};
+
+/// A liquid and what the game shows for it.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Fixture {
+    pub liquid: LiquidData,
+    /// The display name of a potion item with this damage value.
+    pub name: &'static str,
+    /// The effects, in the order of their ids.
+    pub effects: &'static [PotionEffect],
+    /// The shortest recipe from water in letters, smallest first among equally short ones, or
+    /// `None` if the liquid can't be brewed.
+    pub recipe: Option<&'static str>,
+}
+
+/// Plain water, which every recipe starts from.
+pub const WATER: Fixture = Fixture {
+    liquid: LiquidData(0),
+    name: "Water Bottle",
+    effects: &[],
+    recipe: Some(""),
+};
+
+/// Water with sugar, a potion without effects.
+pub const MUNDANE: Fixture = Fixture {
+    liquid: LiquidData(1),
+    name: "Mundane Potion",
+    effects: &[],
+    recipe: Some("S"),
+};
+
+/// Water with a fermented spider eye.
+pub const FERMENTED: Fixture = Fixture {
+    liquid: LiquidData(16896),
+    name: "Potion of Dullness",
+    effects: &[MiningFatigue, Poison],
+    recipe: Some("F"),
+};
+
+/// Water with a spider eye.
+pub const SPIDER_EYE: Fixture = Fixture {
+    liquid: LiquidData(1184),
+    name: "Potion of Slowness",
+    effects: &[Slowness, Hunger],
+    recipe: Some("E"),
+};
+
+/// `SPIDER_EYE` with nether wart, which loses its effects.
+pub const SPIDER_EYE_WART: Fixture = Fixture {
+    liquid: LiquidData(1088),
+    name: "Mundane Potion",
+    effects: &[],
+    recipe: Some("EN"),
+};
+
+/// The shortest potion of strength.
+pub const STRENGTH: Fixture = Fixture {
+    liquid: LiquidData(16910),
+    name: "Potion of Strength",
+    effects: &[Strength, Hunger, Poison],
+    recipe: Some("SECNSGEN"),
+};
+
+/// A liquid with one of the longest recipes.
+pub const SWIFTNESS: Fixture = Fixture {
+    liquid: LiquidData(31011),
+    name: "Potion of Swiftness",
+    effects: &[Swiftness, Hunger],
+    recipe: Some("SECNSENSGNCWEFNSNSG"),
+};
+
+/// A liquid with effects that can't be brewed from water.
+pub const UNREACHABLE: Fixture = Fixture {
+    liquid: LiquidData(8193),
+    name: "Potion of Swiftness",
+    effects: &[Swiftness, Regeneration],
+    recipe: None,
+};
+
+/// Every fixture, in ascending order of their liquids.
+pub const ALL: [Fixture; 8] = [
+    WATER,
+    MUNDANE,
+    SPIDER_EYE_WART,
+    SPIDER_EYE,
+    UNREACHABLE,
+    FERMENTED,
+    STRENGTH,
+    SWIFTNESS,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{ALL, WATER};
+    use crate::solver::RecipeTable;
+    use crate::Recipe;
+
+    #[test]
+    fn fixtures_match_the_crate() {
+        let table = RecipeTable::build();
+        for fixture in ALL.iter() {
+            let ld = fixture.liquid;
+            assert_eq!(ld.name(), fixture.name, "{}", ld.0);
+            assert_eq!(ld.effects(), fixture.effects, "{}", ld.0);
+            assert_eq!(
+                table.recipe(ld).map(|r| r.to_string()).as_deref(),
+                fixture.recipe,
+                "{}",
+                ld.0
+            );
+            if let Some(letters) = fixture.recipe {
+                let recipe: Recipe = letters.parse().unwrap();
+                assert_eq!(recipe.apply(WATER.liquid), ld);
+            }
+        }
+        assert!(ALL.windows(2).all(|w| w[0].liquid < w[1].liquid));
+    }
+}
@@ -0,0 +1,258 @@
+//! The cellular automaton that decides what nether wart does to a liquid.
+
+use core::iter;
+
+/// Represents the cellular automaton used for nether warts.
+///
+/// Each of the 15 low bits is a cell. A live cell survives unless, on either side, its neighbor
+/// is dead while the cell beyond is alive, and a dead cell comes alive between two live ones.
+/// Cells below the lowest count as dead, while those above the highest wrap around to the
+/// lowest ones.
+#[derive(Copy, Clone, Eq, PartialEq, Default, Debug, Hash)]
+pub struct FungalAutomaton(pub u16);
+
+impl FungalAutomaton {
+    /// Calculates the next generation.
+    pub const fn next(&self) -> Self {
+        // Every cell at once: bit i of `up1` is cell i + 1 and so on. Cells above the highest
+        // wrap around to the lowest, while those below the lowest are dead.
+        const CELLS: u16 = 0x7fff;
+        let cells = self.0 & CELLS;
+        let up1 = (cells >> 1 | cells << 14) & CELLS;
+        let up2 = (cells >> 2 | cells << 13) & CELLS;
+        let down1 = cells << 1 & CELLS;
+        let down2 = cells << 2 & CELLS;
+        let survives = cells & (up1 | !up2) & (down1 | !down2);
+        let born = !cells & up1 & down1;
+        Self((survives | born) & CELLS)
+    }
+
+    /// Calculates the next generation one cell at a time, the way the game does, to check `next`
+    /// against.
+    #[cfg(test)]
+    fn next_by_cells(&self) -> Self {
+        let mut next_gen = Self::default();
+        for i in 0..15isize {
+            // The indices here wrap around
+            let bit = if self.at(i) {
+                (self.at(i + 1) || !self.at(i + 2)) && (self.at(i - 1) || !self.at(i - 2))
+            } else {
+                self.at(i - 1) && self.at(i + 1)
+            };
+            next_gen.set(i, bit);
+        }
+        next_gen
+    }
+
+    /// Creates a fungal automaton from the bits in an integer.
+    pub const fn new(v: u16) -> Self {
+        Self(v)
+    }
+
+    /// The bits of this generation.
+    pub const fn as_u16(self) -> u16 {
+        self.0
+    }
+
+    /// Iterates over this generation and all that follow it, without end.
+    pub fn iter_generations(self) -> impl Iterator<Item = Self> {
+        iter::successors(Some(self), |automaton| Some(automaton.next()))
+    }
+
+    /// Runs the automaton until its output stops changing, returning that final generation and
+    /// how many generations it took to get there.
+    ///
+    /// Every automaton gets there, after at most 21 generations.
+    pub const fn stabilize(self) -> (Self, usize) {
+        match self.try_stabilize() {
+            Some(result) => result,
+            None => panic!("every fungal automaton settles"),
+        }
+    }
+
+    /// Like `stabilize`, but returns `None` if the automaton cycles instead of settling.
+    ///
+    /// After the first generation only the 15 low bits can be set, so an automaton that hasn't
+    /// settled after `0x8000` generations has repeated one of them and never will.
+    pub const fn try_stabilize(self) -> Option<(Self, usize)> {
+        let mut current = self;
+        let mut generations = 0;
+        while generations <= 0x8000 {
+            let next = current.next();
+            if next.0 == current.0 {
+                return Some((current, generations));
+            }
+            current = next;
+            generations += 1;
+        }
+        None
+    }
+
+    #[cfg(test)]
+    fn at(&self, index: isize) -> bool {
+        let shift = (index % 15) & 0x1f;
+        if shift < 16 {
+            self.0 & (1 << shift) != 0
+        } else {
+            false
+        }
+    }
+
+    #[cfg(test)]
+    fn set(&mut self, index: isize, v: bool) {
+        if v {
+            self.0 |= (v as u16) << index;
+        }
+    }
+}
+
+impl From<FungalAutomaton> for u16 {
+    fn from(automaton: FungalAutomaton) -> u16 {
+        automaton.as_u16()
+    }
+}
+
+#[cfg(feature = "std")]
+/// Where every 15-bit automaton ends up: the attractor it runs into, either a single generation
+/// that doesn't change any more or a cycle of generations, and how many generations it takes to
+/// reach it.
+#[derive(Clone, Debug)]
+pub struct AttractorLandscape {
+    attractors: Vec<Vec<FungalAutomaton>>,
+    /// fates[state] = (index of the attractor, generations until it is reached)
+    fates: Vec<(usize, usize)>,
+}
+
+#[cfg(feature = "std")]
+impl AttractorLandscape {
+    /// Runs every automaton until it reaches an attractor.
+    pub fn compute() -> Self {
+        let mut attractors: Vec<Vec<FungalAutomaton>> = Vec::new();
+        let mut fates: Vec<Option<(usize, usize)>> = vec![None; 0x8000];
+        // on_path[state] = the start whose path the state is on
+        let mut on_path = vec![u16::MAX; 0x8000];
+        for start in 0..0x8000u16 {
+            let mut path = Vec::new();
+            let mut current = start;
+            let (attractor, mut transient) = loop {
+                if let Some(fate) = fates[current as usize] {
+                    break fate;
+                }
+                if on_path[current as usize] == start {
+                    // the path ran into itself, so everything from there on is a new cycle
+                    let position = path.iter().position(|&s| s == current).unwrap();
+                    let cycle = path.split_off(position);
+                    for &state in &cycle {
+                        fates[state as usize] = Some((attractors.len(), 0));
+                    }
+                    attractors.push(cycle.into_iter().map(FungalAutomaton).collect());
+                    break (attractors.len() - 1, 0);
+                }
+                on_path[current as usize] = start;
+                path.push(current);
+                current = FungalAutomaton(current).next().0;
+            };
+            for &state in path.iter().rev() {
+                transient += 1;
+                fates[state as usize] = Some((attractor, transient));
+            }
+        }
+        AttractorLandscape {
+            attractors,
+            fates: fates.into_iter().map(Option::unwrap).collect(),
+        }
+    }
+
+    /// Lists every attractor, ordered by the lowest automaton that runs into it. A generation that
+    /// doesn't change is an attractor on its own, a cycle lists its generations in order.
+    pub fn attractors(&self) -> &[Vec<FungalAutomaton>] {
+        &self.attractors
+    }
+
+    /// The index in `attractors` of the attractor `automaton` runs into.
+    pub fn attractor_of(&self, automaton: FungalAutomaton) -> usize {
+        self.fates[automaton.0 as usize & 0x7fff].0
+    }
+
+    /// How many generations `automaton` takes to reach its attractor.
+    pub fn transient(&self, automaton: FungalAutomaton) -> usize {
+        self.fates[automaton.0 as usize & 0x7fff].1
+    }
+
+    /// Iterates over every automaton that runs into the attractor with the given index, in
+    /// ascending order.
+    pub fn basin(&self, attractor: usize) -> impl Iterator<Item = FungalAutomaton> + '_ {
+        (0..0x8000)
+            .filter(move |&state| self.fates[state as usize].0 == attractor)
+            .map(FungalAutomaton)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "std")]
+    use crate::fungal::AttractorLandscape;
+    use crate::fungal::FungalAutomaton;
+
+    #[test]
+    fn negative_overflow_is_correct() {
+        assert!(!FungalAutomaton::new(14627).at(-1), "index -1");
+        assert!(!FungalAutomaton::new(14627).at(-2), "index -2");
+    }
+
+    #[test]
+    fn every_automaton_stabilizes() {
+        let mut longest = 0;
+        for v in 0..0x8000 {
+            let start = FungalAutomaton::new(v);
+            let (stable, generations) = start.stabilize();
+            assert_eq!(stable.next(), stable);
+            assert_eq!(start.try_stabilize(), Some((stable, generations)));
+            assert_eq!(start.iter_generations().nth(generations), Some(stable));
+            longest = longest.max(generations);
+        }
+        assert_eq!(longest, 21);
+        assert_eq!(
+            FungalAutomaton::new(0).stabilize(),
+            (FungalAutomaton::new(0), 0)
+        );
+    }
+
+    #[test]
+    fn bit_parallel_matches_cells() {
+        for v in 0..=u16::MAX {
+            let automaton = FungalAutomaton::new(v);
+            assert_eq!(automaton.next(), automaton.next_by_cells(), "{:016b}", v);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn landscape_matches_stabilize() {
+        let landscape = AttractorLandscape::compute();
+        // nothing cycles, so every attractor is a single generation
+        assert!(landscape.attractors().iter().all(|a| a.len() == 1));
+        let fixed_points = (0..0x8000)
+            .filter(|&v| FungalAutomaton::new(v).next() == FungalAutomaton::new(v))
+            .count();
+        assert_eq!(landscape.attractors().len(), fixed_points);
+        for v in 0..0x8000 {
+            let automaton = FungalAutomaton::new(v);
+            let (stable, generations) = automaton.stabilize();
+            let attractor = landscape.attractor_of(automaton);
+            assert_eq!(landscape.attractors()[attractor], vec![stable]);
+            assert_eq!(landscape.transient(automaton), generations);
+        }
+        let empty = landscape.attractor_of(FungalAutomaton::new(0));
+        assert_eq!(landscape.attractors()[empty], vec![FungalAutomaton::new(0)]);
+        assert!(landscape
+            .basin(empty)
+            .all(|automaton| automaton.stabilize().0 == FungalAutomaton::new(0)));
+    }
+
+    #[test]
+    fn positive_overflow_is_correct() {
+        assert!(!FungalAutomaton::new(14627).at(13 + 1), "index 13+1");
+        assert!(FungalAutomaton::new(14627).at(13 + 2), "index 13+2");
+    }
+}
@@ -0,0 +1,137 @@
+//! The graph of liquids connected by actions, for visualizing how potions relate.
+
+use crate::{Action, LiquidData};
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+
+/// Lists every liquid that can be brewed from `start`, in ascending order.
+pub fn reachable(start: LiquidData) -> Vec<LiquidData> {
+    let seen = search(start);
+    LiquidData::all().filter(|ld| seen[ld.0 as usize]).collect()
+}
+
+/// Lists every liquid that can't be brewed from `start`, in ascending order.
+pub fn unreachable(start: LiquidData) -> Vec<LiquidData> {
+    let seen = search(start);
+    LiquidData::all()
+        .filter(|ld| !seen[ld.0 as usize])
+        .collect()
+}
+
+/// Marks every liquid that can be brewed from `start`.
+fn search(start: LiquidData) -> Vec<bool> {
+    let mut seen = vec![false; 0x8000];
+    seen[start.0 as usize] = true;
+    let mut stack = vec![start];
+    while let Some(ld) = stack.pop() {
+        for &action in Action::ALL.iter() {
+            let next = action.apply_to(ld);
+            if !seen[next.0 as usize] {
+                seen[next.0 as usize] = true;
+                stack.push(next);
+            }
+        }
+    }
+    seen
+}
+
+/// Lists every liquid at most `radius` actions away from `center`, in either direction, in
+/// ascending order.
+pub fn neighborhood(center: LiquidData, radius: usize) -> Vec<LiquidData> {
+    let mut found = BTreeSet::new();
+    found.insert(center);
+    let mut frontier = vec![center];
+    for _ in 0..radius {
+        let mut next_frontier = Vec::new();
+        for &ld in &frontier {
+            for &action in Action::ALL.iter() {
+                let neighbors = ld.preimages(action).into_iter();
+                for next in neighbors.chain(Some(action.apply_to(ld))) {
+                    if found.insert(next) {
+                        next_frontier.push(next);
+                    }
+                }
+            }
+        }
+        frontier = next_frontier;
+    }
+    found.into_iter().collect()
+}
+
+/// Writes the graph between `liquids` in Graphviz DOT format.
+///
+/// Every liquid becomes a node filled with its color, and every action that leads from one of
+/// them to another becomes an edge labeled with its letter. Actions with the same result share an
+/// edge.
+pub fn write_dot(mut writer: impl Write, liquids: &[LiquidData]) -> io::Result<()> {
+    let included: BTreeSet<LiquidData> = liquids.iter().copied().collect();
+    writeln!(writer, "digraph brewing {{")?;
+    writeln!(writer, "    node [style=filled];")?;
+    for &ld in &included {
+        writeln!(
+            writer,
+            "    {} [label=\"{}\\n{}\", fillcolor=\"#{:06x}\"];",
+            ld.0,
+            ld.0,
+            ld.name(),
+            ld.color()
+        )?;
+    }
+    for &ld in &included {
+        let mut edges: Vec<(LiquidData, String)> = Vec::new();
+        for &action in Action::ALL.iter() {
+            let next = action.apply_to(ld);
+            if !included.contains(&next) {
+                continue;
+            }
+            match edges.iter_mut().find(|(to, _)| *to == next) {
+                Some((_, label)) => label.push(action.letter()),
+                None => edges.push((next, action.letter().to_string())),
+            }
+        }
+        for (next, label) in edges {
+            writeln!(writer, "    {} -> {} [label=\"{}\"];", ld.0, next.0, label)?;
+        }
+    }
+    writeln!(writer, "}}")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{neighborhood, reachable, unreachable, write_dot};
+    use crate::{LiquidData, PotionIngredient};
+
+    #[test]
+    fn everything_brewable_is_reachable() {
+        assert_eq!(reachable(LiquidData::default()).len(), 5339);
+        let missing = unreachable(LiquidData::default());
+        assert_eq!(missing.len(), 0x8000 - 5339);
+        assert!(!missing.contains(&LiquidData(16896)));
+    }
+
+    #[test]
+    fn neighborhood_goes_both_ways() {
+        let water = LiquidData::default();
+        let fermented = water.apply_ingredient(PotionIngredient::FermentedSpiderEye);
+        assert_eq!(neighborhood(fermented, 0), vec![fermented]);
+        let around = neighborhood(fermented, 1);
+        assert!(around.contains(&water));
+        assert!(around.contains(&fermented.apply_ingredient(PotionIngredient::Sugar)));
+        assert!(around.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn dot_output_lists_nodes_and_edges() {
+        let water = LiquidData::default();
+        let sugar = water.apply_ingredient(PotionIngredient::Sugar);
+        let mut out = Vec::new();
+        write_dot(&mut out, &[water, sugar]).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("digraph brewing {\n"));
+        assert!(out.contains("    0 [label=\"0\\nWater Bottle\", fillcolor=\"#385dc6\"];\n"));
+        assert!(out.contains("    0 -> 1 [label=\"S\"];\n"));
+        // diluting water and adding nether wart to it both leave it as it is
+        assert!(out.contains("    0 -> 0 [label=\"WN\"];\n"));
+        assert!(out.ends_with("}\n"));
+    }
+}
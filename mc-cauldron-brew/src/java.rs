@@ -0,0 +1,153 @@
+//! Conversions between `LiquidData` and the signed integers Java code and NBT files use.
+//!
+//! Item damage is stored as a `TAG_Short`, which Java sign-extends when it is read back into an
+//! `int`. Any value with bit 15 set therefore shows up as a negative number on the Java side.
+//!
+//! `LiquidData::apply_wart_java` brews such numbers the way the game's code does, quirks included.
+
+use crate::LiquidData;
+
+impl LiquidData {
+    /// Reinterprets a Java `short`, e.g. the `Damage` tag of a potion item.
+    pub fn from_java_short(v: i16) -> Self {
+        Self(v as u16)
+    }
+
+    /// Returns the `short` Java would store for this value.
+    pub fn to_java_short(self) -> i16 {
+        self.0 as i16
+    }
+
+    /// Converts a Java `int` holding a damage value.
+    ///
+    /// Both the zero-extended (`0..=65535`) and the sign-extended (`-32768..=-1`) form of a short
+    /// are accepted; anything else can't have come from a short and returns `None`.
+    pub fn from_java_int(v: i32) -> Option<Self> {
+        if (-32768..=65535).contains(&v) {
+            Some(Self(v as u16))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the `int` Java sees after loading this value from a short, i.e. sign-extended.
+    pub fn to_java_int(self) -> i32 {
+        self.to_java_short() as i32
+    }
+
+    /// Adds a nether wart the way the game's code does it on the sign-extended `int`, and stores
+    /// the result back in a short. This is opt-in, for checking behavior against the original.
+    ///
+    /// For the 15-bit liquids, this is the same as `apply_wart`. With bit 15 set, Java sees a
+    /// negative number, so every bit from 15 up is set. The cells below bit 0, which the automaton
+    /// reads as bits `(i % 15) & 31`, that is 30 and 31, aren't dead, and the highest set bit is
+    /// 31 instead of 15, so the leading bit that is put back is lost in the short: the result
+    /// never has bit 15 set, while `apply_wart` keeps it.
+    pub fn apply_wart_java(self) -> Self {
+        Self(java_wart(self.to_java_int()) as u16)
+    }
+}
+
+/// The position of the highest bit that is set, or -1 for 0.
+fn java_first_set(data: i32) -> i32 {
+    31 - (data as u32).leading_zeros() as i32
+}
+
+/// Nether wart on a Java `int`, where shifts wrap and bit 31 is the sign.
+fn java_wart(data: i32) -> i32 {
+    let data = java_wart_stage_1(data);
+    let first_set = java_first_set(data);
+    let mut cells = if first_set >= 0 {
+        data & !(1 << first_set)
+    } else {
+        data
+    };
+    loop {
+        let next = java_next_generation(cells);
+        if next == cells {
+            break;
+        }
+        cells = next;
+    }
+    if first_set >= 0 {
+        cells | 1 << first_set
+    } else {
+        cells
+    }
+}
+
+fn java_wart_stage_1(data: i32) -> i32 {
+    if data & 1 == 0 {
+        return data;
+    }
+    let first_set = java_first_set(data);
+    if first_set < 2 || data & (1 << (first_set - 1)) != 0 {
+        return data;
+    }
+    let mut res = data & !(1 << first_set);
+    res <<= 1;
+    res | 0b11 << (first_set - 1)
+}
+
+/// One generation of the fungal automaton, cell by cell like the game.
+fn java_next_generation(cells: i32) -> i32 {
+    let at = |index: i32| cells & (1 << ((index % 15) & 0x1f)) != 0;
+    let mut next = 0;
+    for i in 0..15 {
+        let alive = if at(i) {
+            (at(i + 1) || !at(i + 2)) && (at(i - 1) || !at(i - 2))
+        } else {
+            at(i - 1) && at(i + 1)
+        };
+        if alive {
+            next |= 1 << i;
+        }
+    }
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::LiquidData;
+
+    #[test]
+    fn fifteen_bit_values_are_unchanged() {
+        assert_eq!(LiquidData(16896).to_java_short(), 16896);
+        assert_eq!(LiquidData(16896).to_java_int(), 16896);
+        assert_eq!(LiquidData::from_java_int(16896), Some(LiquidData(16896)));
+    }
+
+    #[test]
+    fn bit_15_is_negative_in_java() {
+        assert_eq!(LiquidData(0x8001).to_java_short(), -32767);
+        assert_eq!(LiquidData(0x8001).to_java_int(), -32767);
+        assert_eq!(LiquidData::from_java_short(-32767), LiquidData(0x8001));
+        assert_eq!(LiquidData::from_java_int(-32767), Some(LiquidData(0x8001)));
+        assert_eq!(LiquidData::from_java_int(0x8001), Some(LiquidData(0x8001)));
+    }
+
+    #[test]
+    fn java_wart_matches_for_fifteen_bits() {
+        for ld in LiquidData::all() {
+            assert_eq!(ld.apply_wart_java(), ld.apply_wart(), "{}", ld.0);
+        }
+    }
+
+    #[test]
+    fn java_wart_sees_the_sign_bits() {
+        // bit 30 is set, so the first stage leaves the liquid alone, cell 0 dies because the cell
+        // two below it is bit 30, and the leading bit 31 doesn't fit in the short
+        assert_eq!(LiquidData(0x8001).apply_wart_java(), LiquidData(0));
+        assert_eq!(LiquidData(0x8001).apply_wart(), LiquidData(0x8002));
+        for ld in (0x8000..=0xffff).map(LiquidData) {
+            assert_eq!(ld.apply_wart_java().0 & 0x8000, 0, "{}", ld.0);
+            assert_ne!(ld.apply_wart().0 & 0x8000, 0, "{}", ld.0);
+        }
+    }
+
+    #[test]
+    fn out_of_range_ints_are_rejected() {
+        assert_eq!(LiquidData::from_java_int(65536), None);
+        assert_eq!(LiquidData::from_java_int(-32769), None);
+    }
+}
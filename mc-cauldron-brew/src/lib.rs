@@ -1,6 +1,88 @@
-use crate::fungal::FungalAutomaton;
+//! Simulates brewing potions in cauldrons, as of b1.9-pre2, and finds recipes for them.
+//!
+//! Without the default `std` feature, the crate is `no_std` and only has the liquids and the
+//! actions on them, most of which are `const fn`. Effects, names, recipes and the solvers need
+//! `std`.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+use core::fmt;
+
+pub use crate::action::Action;
+pub use crate::cauldron::{CauldronError, CauldronState, MAX_LEVEL};
+#[cfg(feature = "std")]
+pub use crate::color::WATER_COLOR;
+pub use crate::custom::CustomIngredient;
+#[cfg(feature = "std")]
+pub use crate::effect_mask::EffectMask;
+#[cfg(feature = "std")]
+pub use crate::effects::{
+    effect_classes, EffectClass, EffectDetails, ParseEffectError, PotionEffect,
+};
+#[cfg(feature = "std")]
+pub use crate::fungal::AttractorLandscape;
+pub use crate::fungal::FungalAutomaton;
+#[cfg(feature = "std")]
+pub use crate::modern::ModernPotion;
+#[cfg(feature = "std")]
+pub use crate::recipe::{ParseRecipeError, Recipe};
+#[cfg(feature = "std")]
+pub use crate::registry::{IngredientRegistry, RegisterError};
+#[cfg(feature = "std")]
+pub use crate::robustness::Robustness;
+#[cfg(feature = "std")]
+pub use crate::session::CauldronSession;
+#[cfg(feature = "std")]
+pub use crate::steps::{Materials, Step};
+#[cfg(feature = "std")]
+pub use crate::transitions::TransitionTable;
+#[cfg(feature = "std")]
+pub use crate::verify::{verify_recipe, VerifyError};
+
+mod action;
+mod cauldron;
+#[cfg(feature = "std")]
+mod color;
+mod custom;
+#[cfg(feature = "std")]
+mod effect_mask;
+#[cfg(feature = "std")]
+mod effects;
+#[cfg(feature = "std")]
+pub mod fixtures;
+mod fungal;
+#[cfg(feature = "std")]
+pub mod graph;
+#[cfg(feature = "std")]
+mod modern;
+#[cfg(feature = "std")]
+mod naming;
+#[cfg(feature = "nbt")]
+pub mod nbt;
+#[cfg(feature = "std")]
+mod preimage;
+#[cfg(feature = "std")]
+mod recipe;
+#[cfg(feature = "std")]
+mod registry;
+#[cfg(feature = "std")]
+mod robustness;
+#[cfg(feature = "std")]
+mod session;
+#[cfg(feature = "std")]
+pub mod solver;
+#[cfg(feature = "std")]
+mod steps;
+#[cfg(feature = "std")]
+mod transitions;
+#[cfg(feature = "std")]
+mod verify;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum PotionIngredient {
     Sugar,
     GhastTear,
@@ -8,11 +90,25 @@ pub enum PotionIngredient {
     FermentedSpiderEye,
     BlazePowder,
     MagmaCream,
+    /// An ingredient the game doesn't have, e.g. from a mod. With the `serde` feature, serializing
+    /// one fails, as does serializing an `Action` that adds one.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(CustomIngredient),
 }
 
 impl PotionIngredient {
+    /// Every ingredient the game has, in the order of their letters in `Action::ALL`.
+    pub const ALL: [PotionIngredient; 6] = [
+        PotionIngredient::Sugar,
+        PotionIngredient::GhastTear,
+        PotionIngredient::SpiderEye,
+        PotionIngredient::FermentedSpiderEye,
+        PotionIngredient::BlazePowder,
+        PotionIngredient::MagmaCream,
+    ];
+
     /// Lists the bits that are set by this ingredient
-    pub fn added_bits(self) -> &'static [u8] {
+    pub const fn added_bits(self) -> &'static [u8] {
         match self {
             PotionIngredient::Sugar => &[0u8],
             PotionIngredient::GhastTear => &[11u8],
@@ -20,41 +116,72 @@ impl PotionIngredient {
             PotionIngredient::FermentedSpiderEye => &[9u8, 14u8],
             PotionIngredient::BlazePowder => &[14u8],
             PotionIngredient::MagmaCream => &[1u8, 6u8, 14u8],
+            PotionIngredient::Custom(ingredient) => ingredient.added_bits(),
         }
     }
 }
 
 /// Represents the liquidData of a Cauldron tile entity or the damage value of a potion item.
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+///
+/// With the `serde` feature, it is stored as the plain number.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct LiquidData(pub u16);
 
 impl LiquidData {
     /// Calculates the result of adding an ingredient
-    pub fn apply_ingredient(self, ingredient: PotionIngredient) -> Self {
-        let mut result = self;
-        for bit in ingredient.added_bits() {
-            result.0 |= 1 << bit;
+    pub const fn apply_ingredient(self, ingredient: PotionIngredient) -> Self {
+        let bits = ingredient.added_bits();
+        let mut result = self.0;
+        let mut i = 0;
+        while i < bits.len() {
+            result |= 1 << bits[i];
+            i += 1;
         }
-        result
+        Self(result)
+    }
+
+    /// The bits that are cleared by adding water.
+    pub const DILUTED_BITS: u16 =
+        (1 << 1) | (1 << 3) | (1 << 5) | (1 << 7) | (1 << 9) | (1 << 11) | (1 << 13);
+
+    /// Iterates over every 15-bit value, which covers every liquid the game can produce.
+    pub fn all() -> impl Iterator<Item = LiquidData> {
+        (0..0x8000).map(LiquidData)
     }
 
     /// Calculates the result of adding a water bucket
     ///
     /// To do this in-game, you have to first remove a layer using an empty bottle.
-    pub fn dilute(self) -> Self {
-        Self(
-            self.0
-                & !((1 << 1) | (1 << 3) | (1 << 5) | (1 << 7) | (1 << 9) | (1 << 11) | (1 << 13)),
-        )
+    pub const fn dilute(self) -> Self {
+        Self(self.0 & !Self::DILUTED_BITS)
     }
 
     /// Calculates the result of adding a nether wart.
-    pub fn apply_wart(self) -> Self {
-        self.apply_wart_stage_1().apply_automaton()
+    ///
+    /// With the `lut` feature, this looks the result up in a table instead.
+    pub const fn apply_wart(self) -> Self {
+        #[cfg(feature = "lut")]
+        {
+            if (self.0 as usize) < lut::WART.len() {
+                return Self(lut::WART[self.0 as usize]);
+            }
+        }
+        match self.try_apply_wart() {
+            Some(result) => result,
+            None => panic!("the fungal automaton settles for every liquid"),
+        }
+    }
+
+    /// Like `apply_wart`, but returns `None` instead of looping forever if the fungal automaton
+    /// never settles, which doesn't happen for any liquid.
+    pub const fn try_apply_wart(self) -> Option<Self> {
+        self.apply_wart_stage_1().try_apply_automaton()
     }
 
     /// The first step of wart handling.
-    fn apply_wart_stage_1(self) -> Self {
+    const fn apply_wart_stage_1(self) -> Self {
         // If the lowest bit isn't set, return.
         // lowest bit can be set by adding sugar, maybe also using warts?
         if self.0 & 1 == 0 {
@@ -62,24 +189,24 @@ impl LiquidData {
         }
         // find the first occurrence of '10' (from the right) in the data.
         let first_set = self.first_set();
-        if first_set < 2 || (self.0 & (1 << first_set - 1)) != 0 {
+        if first_set < 2 || (self.0 & (1 << (first_set - 1))) != 0 {
             return self;
         }
         // clear every bit that is left of that.
         // e.g. '0011_1001' becomes '0000_1001'
         let mut res = self.0 & !(1 << first_set);
         res <<= 1;
-        res |= 0b11 << first_set - 1;
+        res |= 0b11 << (first_set - 1);
         Self(res)
     }
 
     /// Finds the position of the first bit that is set
-    fn first_set(self) -> i32 {
+    const fn first_set(self) -> i32 {
         math::first_set(self.0)
     }
 
     /// Applies the nether wart automaton
-    fn apply_automaton(self) -> Self {
+    const fn try_apply_automaton(self) -> Option<Self> {
         // Remove the first bit that is set
         let first_set = self.first_set();
         let without_leading_bits = if first_set >= 0 {
@@ -89,14 +216,9 @@ impl LiquidData {
         };
 
         // Run the fungal automaton until its output stops changing
-        let evolved: u16 = {
-            let mut next = FungalAutomaton::new(without_leading_bits);
-            let mut current = FungalAutomaton::default();
-            while current != next {
-                current = next;
-                next = next.next();
-            }
-            current.into()
+        let evolved = match FungalAutomaton::new(without_leading_bits).try_stabilize() {
+            Some((stable, _)) => stable.as_u16(),
+            None => return None,
         };
 
         // Add the bit that was removed above
@@ -106,90 +228,91 @@ impl LiquidData {
             evolved
         };
 
-        Self(result)
+        Some(Self(result))
     }
 }
 
-mod fungal {
-    /// Represents the cellular automaton used for nether warts.
-    #[derive(Copy, Clone, Eq, PartialEq, Default, Debug, Hash)]
-    pub struct FungalAutomaton(pub u16);
-
-    impl FungalAutomaton {
-        /// Calculates the next generation.
-        pub fn next(&self) -> Self {
-            let mut next_gen = Self::default();
-            for i in 0..15isize {
-                // The indices here wrap around
-                let bit = if self.at(i) {
-                    (self.at(i + 1) || !self.at(i + 2)) && (self.at(i - 1) || !self.at(i - 2))
-                } else {
-                    self.at(i - 1) && self.at(i + 1)
-                };
-                next_gen.set(i, bit);
-            }
-            next_gen
-        }
-
-        /// Creates a fungal automaton from the bits in an integer.
-        pub fn new(v: u16) -> Self {
-            Self(v)
-        }
+impl IntoIterator for LiquidData {
+    type Item = u8;
+    type IntoIter = SetBits;
 
-        pub fn as_u16(self) -> u16 {
-            self.0
-        }
+    /// Iterates over the positions of the bits that are set, lowest first.
+    fn into_iter(self) -> SetBits {
+        SetBits(self.0)
+    }
+}
 
-        fn at(&self, index: isize) -> bool {
-            let shift = (index % 15) & 0x1f;
-            if shift < 16 {
-                self.0 & (1 << shift) != 0
-            } else {
-                false
-            }
-        }
+impl fmt::Binary for LiquidData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Binary::fmt(&self.0, f)
+    }
+}
 
-        fn set(&mut self, index: isize, v: bool) {
-            if v {
-                self.0 |= (v as u16) << index;
-            }
-        }
+impl fmt::LowerHex for LiquidData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.0, f)
     }
+}
+
+/// Iterator over the set bits of a `LiquidData`, see `LiquidData::into_iter`.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct SetBits(u16);
+
+impl Iterator for SetBits {
+    type Item = u8;
 
-    impl Into<u16> for FungalAutomaton {
-        fn into(self) -> u16 {
-            self.as_u16()
+    fn next(&mut self) -> Option<u8> {
+        if self.0 == 0 {
+            return None;
         }
+        let bit = self.0.trailing_zeros() as u8;
+        self.0 &= self.0 - 1;
+        Some(bit)
     }
-    #[cfg(test)]
-    mod tests {
-        use crate::fungal::FungalAutomaton;
-
-        #[test]
-        fn negative_overflow_is_correct() {
-            assert_eq!(FungalAutomaton::new(14627).at(-1), false, "index -1");
-            assert_eq!(FungalAutomaton::new(14627).at(-2), false, "index -2");
-        }
 
-        #[test]
-        fn positive_overflow_is_correct() {
-            assert_eq!(FungalAutomaton::new(14627).at(13 + 1), false, "index 13+1");
-            assert_eq!(FungalAutomaton::new(14627).at(13 + 2), true, "index 13+2");
-        }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let count = self.0.count_ones() as usize;
+        (count, Some(count))
     }
 }
 
+impl ExactSizeIterator for SetBits {}
+
+#[cfg(feature = "proptest")]
+mod arbitrary;
+mod java;
+#[cfg(feature = "lut")]
+mod lut;
+
 mod math {
-    pub fn first_set(v: u16) -> i32 {
+    pub const fn first_set(v: u16) -> i32 {
         15 - (v.leading_zeros() as i32)
     }
 }
 
+#[cfg(all(test, feature = "std"))]
+mod snapshot;
+
 #[cfg(test)]
 mod tests {
     use crate::LiquidData;
     use crate::PotionIngredient::{BlazePowder, FermentedSpiderEye, MagmaCream, SpiderEye, Sugar};
 
+    #[test]
+    fn liquids_can_be_brewed_while_compiling() {
+        const FERMENTED: LiquidData = LiquidData(0).dilute().apply_ingredient(FermentedSpiderEye);
+        const AWKWARD: LiquidData = LiquidData(0).apply_ingredient(Sugar).apply_wart();
+        assert_eq!(FERMENTED.0, 16896);
+        assert_eq!(AWKWARD, LiquidData(0).apply_ingredient(Sugar).apply_wart());
+    }
+
+    #[test]
+    fn wart_always_settles() {
+        for v in 0..=u16::MAX {
+            assert!(LiquidData(v).try_apply_wart().is_some(), "{}", v);
+        }
+    }
+
     #[test]
     fn potion_w_is_correct() {
         assert_eq!(LiquidData::default().dilute().0, 0);
@@ -293,4 +416,19 @@ mod tests {
     fn wart_to_31011_is_correct() {
         assert_eq!(LiquidData(31011).apply_wart().0, 16675);
     }
+
+    #[test]
+    fn set_bits_match_ingredient() {
+        let bits: Vec<u8> = LiquidData::default()
+            .apply_ingredient(SpiderEye)
+            .into_iter()
+            .collect();
+        assert_eq!(bits, SpiderEye.added_bits());
+    }
+
+    #[test]
+    fn formatting_matches_u16() {
+        assert_eq!(format!("{:#017b}", LiquidData(16896)), "0b100001000000000");
+        assert_eq!(format!("{:04x}", LiquidData(16896)), "4200");
+    }
 }
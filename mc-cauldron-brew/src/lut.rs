@@ -0,0 +1,33 @@
+//! A table with the result of adding nether wart to every liquid, so that `LiquidData::apply_wart`
+//! doesn't have to run the fungal automaton again and again.
+//!
+//! The table is computed while compiling and takes 64 KiB.
+
+use crate::LiquidData;
+
+/// WART[dv] = the dv after adding nether wart, for every 15-bit liquid
+pub(crate) static WART: [u16; 0x8000] = {
+    let mut table = [0; 0x8000];
+    let mut dv = 0;
+    while dv < table.len() {
+        table[dv] = match LiquidData(dv as u16).try_apply_wart() {
+            Some(result) => result.0,
+            None => panic!("the fungal automaton settles for every liquid"),
+        };
+        dv += 1;
+    }
+    table
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::LiquidData;
+
+    #[test]
+    fn table_matches_automaton() {
+        for v in 0..=u16::MAX {
+            let ld = LiquidData(v);
+            assert_eq!(Some(ld.apply_wart()), ld.try_apply_wart(), "{}", v);
+        }
+    }
+}
@@ -0,0 +1,247 @@
+//! Converting liquids to the potions of modern versions, for upgrading old worlds.
+//!
+//! Modern versions only know a fixed list of potion types, so a liquid becomes one of them if it
+//! has exactly that type's effect, tier and duration. Any other liquid with effects keeps them as
+//! custom effects and its color, without a potion type, since a type would add its own effects on
+//! top. Liquids without effects become the modern potion with the same prefix if there is one, and
+//! mundane potions otherwise.
+
+use crate::{EffectDetails, LiquidData, PotionEffect};
+
+/// A potion item as modern versions store it.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ModernPotion {
+    /// The namespaced potion type, e.g. "minecraft:long_poison", if one matches.
+    pub potion: Option<&'static str>,
+    /// Effects that are applied on top of the potion type's.
+    pub custom_effects: Vec<EffectDetails>,
+    /// The color of the liquid, if the potion type doesn't give it.
+    pub custom_color: Option<u32>,
+}
+
+/// The modern potion types with one effect: name, effect, amplifier and duration in ticks.
+const POTION_TYPES: [(&str, PotionEffect, u8, u32); 32] = [
+    ("minecraft:night_vision", PotionEffect::NightVision, 0, 3600),
+    (
+        "minecraft:long_night_vision",
+        PotionEffect::NightVision,
+        0,
+        9600,
+    ),
+    (
+        "minecraft:invisibility",
+        PotionEffect::Invisibility,
+        0,
+        3600,
+    ),
+    (
+        "minecraft:long_invisibility",
+        PotionEffect::Invisibility,
+        0,
+        9600,
+    ),
+    ("minecraft:leaping", PotionEffect::Leaping, 0, 3600),
+    ("minecraft:long_leaping", PotionEffect::Leaping, 0, 9600),
+    ("minecraft:strong_leaping", PotionEffect::Leaping, 1, 1800),
+    (
+        "minecraft:fire_resistance",
+        PotionEffect::FireResistance,
+        0,
+        3600,
+    ),
+    (
+        "minecraft:long_fire_resistance",
+        PotionEffect::FireResistance,
+        0,
+        9600,
+    ),
+    ("minecraft:swiftness", PotionEffect::Swiftness, 0, 3600),
+    ("minecraft:long_swiftness", PotionEffect::Swiftness, 0, 9600),
+    (
+        "minecraft:strong_swiftness",
+        PotionEffect::Swiftness,
+        1,
+        1800,
+    ),
+    ("minecraft:slowness", PotionEffect::Slowness, 0, 1800),
+    ("minecraft:long_slowness", PotionEffect::Slowness, 0, 4800),
+    ("minecraft:strong_slowness", PotionEffect::Slowness, 3, 400),
+    (
+        "minecraft:water_breathing",
+        PotionEffect::WaterBreathing,
+        0,
+        3600,
+    ),
+    (
+        "minecraft:long_water_breathing",
+        PotionEffect::WaterBreathing,
+        0,
+        9600,
+    ),
+    ("minecraft:healing", PotionEffect::Healing, 0, 1),
+    ("minecraft:strong_healing", PotionEffect::Healing, 1, 1),
+    ("minecraft:harming", PotionEffect::Harming, 0, 1),
+    ("minecraft:strong_harming", PotionEffect::Harming, 1, 1),
+    ("minecraft:poison", PotionEffect::Poison, 0, 900),
+    ("minecraft:long_poison", PotionEffect::Poison, 0, 1800),
+    ("minecraft:strong_poison", PotionEffect::Poison, 1, 432),
+    ("minecraft:regeneration", PotionEffect::Regeneration, 0, 900),
+    (
+        "minecraft:long_regeneration",
+        PotionEffect::Regeneration,
+        0,
+        1800,
+    ),
+    (
+        "minecraft:strong_regeneration",
+        PotionEffect::Regeneration,
+        1,
+        450,
+    ),
+    ("minecraft:strength", PotionEffect::Strength, 0, 3600),
+    ("minecraft:long_strength", PotionEffect::Strength, 0, 9600),
+    ("minecraft:strong_strength", PotionEffect::Strength, 1, 1800),
+    ("minecraft:weakness", PotionEffect::Weakness, 0, 1800),
+    ("minecraft:long_weakness", PotionEffect::Weakness, 0, 4800),
+];
+
+impl PotionEffect {
+    /// The namespaced id modern versions use for this effect, e.g. "minecraft:jump_boost".
+    pub fn modern_id(self) -> &'static str {
+        match self {
+            PotionEffect::Swiftness => "minecraft:speed",
+            PotionEffect::Slowness => "minecraft:slowness",
+            PotionEffect::Haste => "minecraft:haste",
+            PotionEffect::MiningFatigue => "minecraft:mining_fatigue",
+            PotionEffect::Strength => "minecraft:strength",
+            PotionEffect::Healing => "minecraft:instant_health",
+            PotionEffect::Harming => "minecraft:instant_damage",
+            PotionEffect::Leaping => "minecraft:jump_boost",
+            PotionEffect::Nausea => "minecraft:nausea",
+            PotionEffect::Regeneration => "minecraft:regeneration",
+            PotionEffect::Resistance => "minecraft:resistance",
+            PotionEffect::FireResistance => "minecraft:fire_resistance",
+            PotionEffect::WaterBreathing => "minecraft:water_breathing",
+            PotionEffect::Invisibility => "minecraft:invisibility",
+            PotionEffect::Blindness => "minecraft:blindness",
+            PotionEffect::NightVision => "minecraft:night_vision",
+            PotionEffect::Hunger => "minecraft:hunger",
+            PotionEffect::Weakness => "minecraft:weakness",
+            PotionEffect::Poison => "minecraft:poison",
+        }
+    }
+}
+
+impl LiquidData {
+    /// The modern potion that does the same as a potion with this damage value.
+    pub fn to_modern(self) -> ModernPotion {
+        let effects = self.effect_details();
+        let potion = match effects.as_slice() {
+            [] if self.0 == 0 => Some("minecraft:water"),
+            [] => match self.prefix() {
+                "Awkward" => Some("minecraft:awkward"),
+                "Thick" => Some("minecraft:thick"),
+                _ => Some("minecraft:mundane"),
+            },
+            [only] => POTION_TYPES
+                .iter()
+                .find(|&&(_, effect, amplifier, duration)| {
+                    (only.effect, only.amplifier, only.duration) == (effect, amplifier, duration)
+                })
+                .map(|&(name, ..)| name),
+            _ => None,
+        };
+        if potion.is_some() {
+            return ModernPotion {
+                potion,
+                custom_effects: Vec::new(),
+                custom_color: None,
+            };
+        }
+        ModernPotion {
+            potion: None,
+            custom_effects: effects,
+            custom_color: Some(self.color()),
+        }
+    }
+}
+
+#[cfg(feature = "nbt")]
+impl ModernPotion {
+    /// Writes the `tag` compound of the potion item, as versions 1.13 to 1.20.4 store it.
+    ///
+    /// Custom effects are stored with their numeric ids, which haven't changed since b1.9-pre2.
+    pub fn to_nbt(&self) -> quartz_nbt::NbtCompound {
+        use quartz_nbt::{NbtCompound, NbtList};
+
+        let mut tag = NbtCompound::new();
+        if let Some(potion) = self.potion {
+            tag.insert("Potion", potion);
+        }
+        if !self.custom_effects.is_empty() {
+            let mut effects = NbtList::new();
+            for details in &self.custom_effects {
+                let mut effect = NbtCompound::new();
+                effect.insert("Id", details.effect.id() as i8);
+                effect.insert("Amplifier", details.amplifier as i8);
+                effect.insert("Duration", details.duration as i32);
+                effects.push(effect);
+            }
+            tag.insert("CustomPotionEffects", effects);
+        }
+        if let Some(color) = self.custom_color {
+            tag.insert("CustomPotionColor", color as i32);
+        }
+        tag
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::POTION_TYPES;
+    use crate::{EffectDetails, LiquidData, PotionEffect};
+
+    #[test]
+    fn exact_matches_use_a_potion_type() {
+        // Healing from a ghast tear
+        let modern = LiquidData(1 << 11).to_modern();
+        assert_eq!(modern.potion, Some("minecraft:healing"));
+        assert!(modern.custom_effects.is_empty());
+        assert_eq!(modern.custom_color, None);
+        for ld in LiquidData::all() {
+            let modern = ld.to_modern();
+            let potion_type = POTION_TYPES
+                .iter()
+                .find(|&&(name, ..)| Some(name) == modern.potion);
+            if let Some(&(_, effect, amplifier, duration)) = potion_type {
+                let expected = EffectDetails {
+                    effect,
+                    amplifier,
+                    duration,
+                };
+                assert_eq!(ld.effect_details(), vec![expected]);
+            }
+        }
+    }
+
+    #[test]
+    fn other_potions_keep_their_effects() {
+        // Mining Fatigue and Poison II
+        let ld = LiquidData(16896);
+        let modern = ld.to_modern();
+        assert_eq!(modern.potion, None);
+        assert_eq!(modern.custom_effects, ld.effect_details());
+        assert_eq!(modern.custom_color, Some(ld.color()));
+        assert_eq!(PotionEffect::Leaping.modern_id(), "minecraft:jump_boost");
+    }
+
+    #[test]
+    fn potions_without_effects_keep_their_base() {
+        assert_eq!(LiquidData(0).to_modern().potion, Some("minecraft:water"));
+        assert_eq!(LiquidData(1).to_modern().potion, Some("minecraft:mundane"));
+        assert_eq!(
+            LiquidData(8 << 1).to_modern().potion,
+            Some("minecraft:awkward")
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! Display names of potion items, as the game derives them from the damage value.
+
+use crate::{LiquidData, PotionEffect};
+
+/// Prefixes for potions without effects, indexed by bits 1 to 5 of the damage value.
+const PREFIXES: [&str; 32] = [
+    "Mundane",
+    "Uninteresting",
+    "Bland",
+    "Clear",
+    "Milky",
+    "Diffuse",
+    "Artless",
+    "Thin",
+    "Awkward",
+    "Flat",
+    "Bulky",
+    "Bungling",
+    "Buttered",
+    "Smooth",
+    "Suave",
+    "Debonair",
+    "Thick",
+    "Elegant",
+    "Fancy",
+    "Charming",
+    "Dashing",
+    "Refined",
+    "Cordial",
+    "Sparkling",
+    "Potent",
+    "Foul",
+    "Odorless",
+    "Rank",
+    "Harsh",
+    "Acrid",
+    "Gross",
+    "Stinky",
+];
+
+impl PotionEffect {
+    /// The name of a potion whose first effect is this one, e.g. "Potion of Swiftness".
+    pub fn potion_name(self) -> String {
+        match self {
+            PotionEffect::MiningFatigue => "Potion of Dullness".to_string(),
+            _ => format!("Potion of {}", self.name()),
+        }
+    }
+}
+
+impl LiquidData {
+    /// The prefix used in the name of this potion if it has no effects, e.g. "Artless".
+    pub fn prefix(self) -> &'static str {
+        PREFIXES[((self.0 >> 1) & 0x1f) as usize]
+    }
+
+    /// The display name of a potion item with this damage value.
+    ///
+    /// Plain water is a "Water Bottle". Potions are named after their first effect, and potions
+    /// without effects get a prefix chosen by bits 1 to 5, e.g. "Artless Potion".
+    pub fn name(self) -> String {
+        if self.0 == 0 {
+            return "Water Bottle".to_string();
+        }
+        match self.effects().first() {
+            Some(effect) => effect.potion_name(),
+            None => format!("{} Potion", self.prefix()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::{FERMENTED, MUNDANE, WATER};
+    use crate::LiquidData;
+    use crate::PotionIngredient::{FermentedSpiderEye, GhastTear, Sugar};
+
+    #[test]
+    fn water_is_a_water_bottle() {
+        assert_eq!(WATER.liquid.name(), WATER.name);
+    }
+
+    #[test]
+    fn potions_are_named_after_their_first_effect() {
+        let ghast = LiquidData::default().apply_ingredient(GhastTear);
+        assert_eq!(ghast.name(), "Potion of Healing");
+        let fermented = LiquidData::default().apply_ingredient(FermentedSpiderEye);
+        assert_eq!(fermented, FERMENTED.liquid);
+        assert_eq!(fermented.name(), FERMENTED.name);
+    }
+
+    #[test]
+    fn potions_without_effects_use_a_prefix() {
+        let sugar = LiquidData::default().apply_ingredient(Sugar);
+        assert!(sugar.effects().is_empty());
+        assert_eq!(sugar, MUNDANE.liquid);
+        assert_eq!(sugar.name(), MUNDANE.name);
+        assert_eq!(LiquidData(0b1100).prefix(), "Artless");
+    }
+}
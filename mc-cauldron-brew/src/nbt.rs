@@ -0,0 +1,167 @@
+//! Reading and writing cauldron tile entities as NBT.
+//!
+//! A cauldron is a compound with the tile entity `id` "Cauldron", its position in `x`, `y` and
+//! `z`, its `liquidData` and its fill `level` from 0 to 3. When reading, integers may have any
+//! width and `liquidData` may be sign-extended the way Java loads a short, see
+//! `LiquidData::from_java_int`. When writing, `liquidData` is an int and `level` a byte.
+
+use crate::{CauldronState, LiquidData};
+use quartz_nbt::{NbtCompound, NbtTag};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+/// The tile entity id of cauldrons.
+pub const CAULDRON_ID: &str = "Cauldron";
+
+/// A cauldron as it is stored in a chunk.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct CauldronEntity {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    pub liquid: LiquidData,
+    /// How many bottles can still be filled, from 0 to 3.
+    pub level: u8,
+}
+
+impl CauldronEntity {
+    /// Reads a cauldron from its tile entity compound.
+    pub fn from_nbt(compound: &NbtCompound) -> Result<Self, CauldronNbtError> {
+        match compound.get::<_, &str>("id") {
+            Ok(CAULDRON_ID) => {}
+            Ok(id) => return Err(CauldronNbtError::NotACauldron(id.to_string())),
+            Err(_) => return Err(CauldronNbtError::Missing("id")),
+        }
+        let level = integer(compound, "level")?;
+        if !(0..=3).contains(&level) {
+            return Err(CauldronNbtError::OutOfRange("level"));
+        }
+        let liquid = i32::try_from(integer(compound, "liquidData")?)
+            .ok()
+            .and_then(LiquidData::from_java_int)
+            .ok_or(CauldronNbtError::OutOfRange("liquidData"))?;
+        let coordinate = |key| {
+            i32::try_from(integer(compound, key)?).map_err(|_| CauldronNbtError::OutOfRange(key))
+        };
+        Ok(CauldronEntity {
+            x: coordinate("x")?,
+            y: coordinate("y")?,
+            z: coordinate("z")?,
+            liquid,
+            level: level as u8,
+        })
+    }
+
+    /// The liquid and fill level of the cauldron.
+    pub fn state(&self) -> CauldronState {
+        CauldronState {
+            liquid: self.liquid,
+            level: self.level,
+        }
+    }
+
+    /// Writes the tile entity compound for this cauldron.
+    pub fn to_nbt(&self) -> NbtCompound {
+        let mut compound = NbtCompound::new();
+        compound.insert("id", CAULDRON_ID);
+        compound.insert("x", self.x);
+        compound.insert("y", self.y);
+        compound.insert("z", self.z);
+        compound.insert("liquidData", self.liquid.to_java_int());
+        compound.insert("level", self.level as i8);
+        compound
+    }
+}
+
+/// Reads an integer tag of any width.
+fn integer(compound: &NbtCompound, key: &'static str) -> Result<i64, CauldronNbtError> {
+    match compound.get::<_, &NbtTag>(key) {
+        Ok(NbtTag::Byte(v)) => Ok(*v as i64),
+        Ok(NbtTag::Short(v)) => Ok(*v as i64),
+        Ok(NbtTag::Int(v)) => Ok(*v as i64),
+        Ok(NbtTag::Long(v)) => Ok(*v),
+        Ok(_) => Err(CauldronNbtError::WrongType(key)),
+        Err(_) => Err(CauldronNbtError::Missing(key)),
+    }
+}
+
+/// The error returned when a compound isn't a cauldron that can be read.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum CauldronNbtError {
+    /// The compound is a tile entity with this other id.
+    NotACauldron(String),
+    /// A tag is missing.
+    Missing(&'static str),
+    /// A tag isn't an integer.
+    WrongType(&'static str),
+    /// An integer tag has a value no cauldron can have.
+    OutOfRange(&'static str),
+}
+
+impl fmt::Display for CauldronNbtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CauldronNbtError::NotACauldron(id) => write!(f, "'{}' is not a cauldron", id),
+            CauldronNbtError::Missing(key) => write!(f, "missing tag '{}'", key),
+            CauldronNbtError::WrongType(key) => write!(f, "tag '{}' is not an integer", key),
+            CauldronNbtError::OutOfRange(key) => write!(f, "tag '{}' is out of range", key),
+        }
+    }
+}
+
+impl Error for CauldronNbtError {}
+
+#[cfg(test)]
+mod tests {
+    use super::{CauldronEntity, CauldronNbtError};
+    use crate::LiquidData;
+    use quartz_nbt::io::{read_nbt, write_nbt, Flavor};
+    use quartz_nbt::snbt;
+
+    #[test]
+    fn cauldrons_round_trip() {
+        let cauldron = CauldronEntity {
+            x: -12,
+            y: 64,
+            z: 300,
+            liquid: LiquidData(0xc042),
+            level: 2,
+        };
+        let mut bytes = Vec::new();
+        write_nbt(&mut bytes, None, &cauldron.to_nbt(), Flavor::Uncompressed).unwrap();
+        let (compound, _) = read_nbt(&mut &bytes[..], Flavor::Uncompressed).unwrap();
+        assert_eq!(CauldronEntity::from_nbt(&compound), Ok(cauldron));
+    }
+
+    #[test]
+    fn other_widths_are_read() {
+        let compound =
+            snbt::parse(r#"{id:"Cauldron",x:1,y:2s,z:3b,liquidData:-16384s,level:3}"#).unwrap();
+        let cauldron = CauldronEntity::from_nbt(&compound).unwrap();
+        assert_eq!((cauldron.x, cauldron.y, cauldron.z), (1, 2, 3));
+        assert_eq!(cauldron.liquid, LiquidData(0xc000));
+        assert_eq!(cauldron.level, 3);
+    }
+
+    #[test]
+    fn bad_compounds_are_rejected() {
+        let read = |s: &str| CauldronEntity::from_nbt(&snbt::parse(s).unwrap());
+        assert_eq!(
+            read(r#"{id:"Furnace",x:0,y:0,z:0}"#),
+            Err(CauldronNbtError::NotACauldron("Furnace".to_string()))
+        );
+        assert_eq!(
+            read(r#"{id:"Cauldron",x:0,y:0,z:0,level:0}"#),
+            Err(CauldronNbtError::Missing("liquidData"))
+        );
+        assert_eq!(
+            read(r#"{id:"Cauldron",x:0,y:0,z:0,liquidData:0,level:4}"#),
+            Err(CauldronNbtError::OutOfRange("level"))
+        );
+        assert_eq!(
+            read(r#"{id:"Cauldron",x:0,y:0,z:0,liquidData:"water",level:0}"#),
+            Err(CauldronNbtError::WrongType("liquidData"))
+        );
+    }
+}
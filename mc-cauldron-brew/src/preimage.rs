@@ -0,0 +1,206 @@
+//! Backward reasoning: which liquids turn into a given one.
+
+use crate::{Action, LiquidData};
+use std::sync::OnceLock;
+
+impl LiquidData {
+    /// Lists every 15-bit liquid that turns into this one when taking `action`, in ascending order.
+    ///
+    /// Ingredients and dilution only touch a few bits, so their preimages are computed directly.
+    /// Nether wart has no such shortcut, so the first call applies it to all 32768 values and
+    /// keeps the results around for later calls.
+    pub fn preimages(self, action: Action) -> Vec<LiquidData> {
+        match action {
+            Action::AddIngredient(ingredient) => {
+                let mask = ingredient
+                    .added_bits()
+                    .iter()
+                    .fold(0u16, |mask, bit| mask | 1 << bit);
+                if self.0 & mask != mask {
+                    return Vec::new();
+                }
+                subsets(mask)
+                    .map(|bits| LiquidData(self.0 & !mask | bits))
+                    .collect()
+            }
+            Action::Dilute => {
+                if self.0 & Self::DILUTED_BITS != 0 {
+                    return Vec::new();
+                }
+                subsets(Self::DILUTED_BITS)
+                    .map(|bits| LiquidData(self.0 | bits))
+                    .collect()
+            }
+            Action::AddNetherWart => wart_preimages(self).to_vec(),
+        }
+    }
+
+    /// Counts the different liquids that taking one of `actions` gives, this one included if an
+    /// action leaves it as it is.
+    pub fn out_degree(self, actions: &[Action]) -> usize {
+        let mut successors: Vec<LiquidData> = actions.iter().map(|a| a.apply_to(self)).collect();
+        successors.sort();
+        successors.dedup();
+        successors.len()
+    }
+
+    /// Counts the different 15-bit liquids that turn into this one when taking one of `actions`,
+    /// this one included if an action leaves it as it is.
+    pub fn in_degree(self, actions: &[Action]) -> usize {
+        let mut predecessors: Vec<LiquidData> =
+            actions.iter().flat_map(|&a| self.preimages(a)).collect();
+        predecessors.sort();
+        predecessors.dedup();
+        predecessors.len()
+    }
+}
+
+/// Looks up the liquids that turn into `target` when adding nether wart, in ascending order.
+pub(crate) fn wart_preimages(target: LiquidData) -> &'static [LiquidData] {
+    static INDEX: OnceLock<WartIndex> = OnceLock::new();
+    let index = INDEX.get_or_init(WartIndex::build);
+    match target.0 as usize {
+        t if t < 0x8000 => {
+            &index.preimages[index.offsets[t] as usize..index.offsets[t + 1] as usize]
+        }
+        _ => &[],
+    }
+}
+
+/// All wart preimages, grouped by their image.
+struct WartIndex {
+    /// The preimages of `t` are at `preimages[offsets[t]..offsets[t + 1]]`.
+    offsets: Vec<u32>,
+    preimages: Vec<LiquidData>,
+}
+
+impl WartIndex {
+    fn build() -> Self {
+        let images: Vec<u16> = LiquidData::all().map(|ld| ld.apply_wart().0).collect();
+        let mut offsets = vec![0u32; 0x8001];
+        for &image in &images {
+            offsets[image as usize + 1] += 1;
+        }
+        for i in 1..offsets.len() {
+            offsets[i] += offsets[i - 1];
+        }
+        let mut next = offsets.clone();
+        let mut preimages = vec![LiquidData::default(); images.len()];
+        for (ld, &image) in LiquidData::all().zip(images.iter()) {
+            preimages[next[image as usize] as usize] = ld;
+            next[image as usize] += 1;
+        }
+        WartIndex { offsets, preimages }
+    }
+}
+
+/// Iterates over all subsets of the bits in `mask`, in ascending order.
+fn subsets(mask: u16) -> impl Iterator<Item = u16> {
+    let mut next = Some(0u16);
+    std::iter::from_fn(move || {
+        let current = next?;
+        // Adding the unused bits makes the carry skip over them.
+        let successor = (current | !mask).wrapping_add(1) & mask;
+        next = if successor == 0 {
+            None
+        } else {
+            Some(successor)
+        };
+        Some(current)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::{self, SPIDER_EYE, SPIDER_EYE_WART, SWIFTNESS, WATER};
+    use crate::preimage::subsets;
+    use crate::{Action, LiquidData, PotionIngredient};
+    use std::collections::HashSet;
+
+    /// Finds the preimages by trying every value.
+    fn brute_force(target: LiquidData, action: Action) -> Vec<LiquidData> {
+        LiquidData::all()
+            .filter(|ld| action.apply_to(*ld) == target)
+            .collect()
+    }
+
+    #[test]
+    fn subsets_are_complete_and_sorted() {
+        let all: Vec<_> = subsets(0b1010).collect();
+        assert_eq!(all, vec![0b0000, 0b0010, 0b1000, 0b1010]);
+        assert_eq!(subsets(0).collect::<Vec<_>>(), vec![0]);
+    }
+
+    #[test]
+    fn ingredient_preimages_match_brute_force() {
+        let eye = Action::AddIngredient(PotionIngredient::SpiderEye);
+        for target in [
+            SPIDER_EYE.liquid,
+            LiquidData(1185),
+            LiquidData(0),
+            SWIFTNESS.liquid,
+        ] {
+            assert_eq!(target.preimages(eye), brute_force(target, eye));
+        }
+    }
+
+    #[test]
+    fn dilute_preimages_match_brute_force() {
+        for target in [
+            LiquidData(0),
+            LiquidData(16384),
+            LiquidData(20485),
+            LiquidData(2),
+        ] {
+            assert_eq!(
+                target.preimages(Action::Dilute),
+                brute_force(target, Action::Dilute)
+            );
+        }
+    }
+
+    #[test]
+    fn wart_preimages_match_brute_force() {
+        for target in [
+            SPIDER_EYE_WART.liquid,
+            LiquidData(0),
+            LiquidData(16675),
+            LiquidData(3),
+        ] {
+            assert_eq!(
+                target.preimages(Action::AddNetherWart),
+                brute_force(target, Action::AddNetherWart)
+            );
+        }
+    }
+
+    #[test]
+    fn degrees_match_brute_force() {
+        let eye = Action::AddIngredient(PotionIngredient::SpiderEye);
+        let subsets: [&[Action]; 3] = [&Action::ALL, &[Action::Dilute, eye], &[]];
+        for fixture in fixtures::ALL.iter() {
+            let ld = fixture.liquid;
+            for &actions in subsets.iter() {
+                let successors: HashSet<_> = actions.iter().map(|a| a.apply_to(ld)).collect();
+                assert_eq!(ld.out_degree(actions), successors.len());
+                let predecessors = LiquidData::all()
+                    .filter(|&prev| actions.iter().any(|a| a.apply_to(prev) == ld))
+                    .count();
+                assert_eq!(ld.in_degree(actions), predecessors, "{}", ld.0);
+            }
+        }
+        // diluting gives water from any liquid with only diluted bits, and leaves it as it is like
+        // nether wart does
+        assert_eq!(WATER.liquid.in_degree(&[Action::Dilute]), 128);
+        assert_eq!(WATER.liquid.out_degree(&Action::ALL), 7);
+    }
+
+    #[test]
+    fn wart_preimages_map_to_the_target() {
+        let preimages = SPIDER_EYE_WART.liquid.preimages(Action::AddNetherWart);
+        assert!(preimages.contains(&SPIDER_EYE.liquid));
+        for ld in preimages {
+            assert_eq!(ld.apply_wart(), SPIDER_EYE_WART.liquid);
+        }
+    }
+}
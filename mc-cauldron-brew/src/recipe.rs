@@ -0,0 +1,255 @@
+use crate::{Action, LiquidData};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// A sequence of actions, written as one letter per action (e.g. "WEFN").
+///
+/// Recipes are ordered like their letters, action by action. With the `serde` feature, they are
+/// stored in the same letter notation. Custom ingredients are written as their letters too, but
+/// only the letters of `Action::ALL` are read back, so recipes with custom ingredients need
+/// `IngredientRegistry::parse_recipe` instead.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct Recipe(pub Vec<Action>);
+
+impl Recipe {
+    /// Calculates the result of taking every action in order.
+    pub fn apply(&self, start: LiquidData) -> LiquidData {
+        start.apply_all(self.0.iter().copied())
+    }
+
+    /// Iterates over every action together with the liquid right after taking it.
+    pub fn states(&self, start: LiquidData) -> impl Iterator<Item = (Action, LiquidData)> + '_ {
+        self.0.iter().scan(start, |ld, &action| {
+            *ld = action.apply_to(*ld);
+            Some((action, *ld))
+        })
+    }
+
+    /// Removes and replaces steps that aren't needed to brew the same liquid from `start`, e.g.
+    /// to clean up a recipe written by hand.
+    ///
+    /// Steps that return to an earlier liquid are dropped, runs of steps that a single action can
+    /// take are replaced by it, and then single steps are dropped if the result stays the same,
+    /// until nothing changes. The result is never longer, but it isn't always the shortest
+    /// recipe, which `solver::breadth_first_from` finds.
+    pub fn simplify(&self, start: LiquidData) -> Recipe {
+        let mut actions = self.0.clone();
+        // custom ingredients in the recipe may replace steps as well
+        let mut candidates: Vec<Action> = Action::ALL.iter().chain(&self.0).copied().collect();
+        candidates.sort();
+        candidates.dedup();
+        let result = self.apply(start);
+        'simplify: loop {
+            let liquids: Vec<LiquidData> = std::iter::once(start)
+                .chain(Recipe(actions.clone()).states(start).map(|(_, ld)| ld))
+                .collect();
+            // the longest run from each liquid first, so that loops go in one piece
+            for i in 0..actions.len() {
+                for j in (i + 1..=actions.len()).rev() {
+                    if liquids[i] == liquids[j] {
+                        actions.drain(i..j);
+                        continue 'simplify;
+                    }
+                    if j - i < 2 {
+                        continue;
+                    }
+                    let shortcut = candidates
+                        .iter()
+                        .find(|a| a.apply_to(liquids[i]) == liquids[j]);
+                    if let Some(&action) = shortcut {
+                        actions.splice(i..j, Some(action));
+                        continue 'simplify;
+                    }
+                }
+            }
+            for i in 0..actions.len() {
+                let mut without = actions.clone();
+                without.remove(i);
+                if start.apply_all(without.iter().copied()) == result {
+                    actions = without;
+                    continue 'simplify;
+                }
+            }
+            return Recipe(actions);
+        }
+    }
+}
+
+impl fmt::Display for Recipe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for action in &self.0 {
+            write!(f, "{}", action)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Recipe {
+    type Err = ParseRecipeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.chars()
+            .enumerate()
+            .map(|(position, letter)| {
+                Action::from_letter(letter).ok_or(ParseRecipeError { position, letter })
+            })
+            .collect::<Result<_, _>>()
+            .map(Recipe)
+    }
+}
+
+/// The error returned when a recipe contains a letter that isn't an action.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ParseRecipeError {
+    /// The index of the offending character.
+    pub position: usize,
+    pub letter: char,
+}
+
+impl fmt::Display for ParseRecipeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown action '{}' at position {}",
+            self.letter, self.position
+        )
+    }
+}
+
+impl Error for ParseRecipeError {}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Recipe {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Recipe {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let letters = String::deserialize(deserializer)?;
+        letters.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::{self, WATER};
+    use crate::{Action, LiquidData, ParseRecipeError, PotionIngredient, Recipe};
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn recipes_serialize_as_letters() {
+        let recipe: Recipe = "WEFN".parse().unwrap();
+        let json = serde_json::to_string(&recipe).unwrap();
+        assert_eq!(json, "\"WEFN\"");
+        assert_eq!(
+            serde_json::from_reader::<_, Recipe>(json.as_bytes()).unwrap(),
+            recipe
+        );
+        assert!(serde_json::from_str::<Recipe>("\"WEX\"").is_err());
+
+        let ld = LiquidData(16896);
+        assert_eq!(serde_json::to_string(&ld).unwrap(), "16896");
+        assert_eq!(serde_json::from_str::<LiquidData>("16896").unwrap(), ld);
+        let action = Action::AddIngredient(PotionIngredient::Sugar);
+        let json = serde_json::to_string(&action).unwrap();
+        assert_eq!(serde_json::from_str::<Action>(&json).unwrap(), action);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn custom_ingredients_only_serialize_in_recipes() {
+        let pixie_dust =
+            PotionIngredient::Custom(crate::CustomIngredient::new("Pixie Dust", 'P', &[2, 12]));
+        assert!(serde_json::to_string(&pixie_dust).is_err());
+        let action = Action::AddIngredient(pixie_dust);
+        let err = serde_json::to_string(&action).unwrap_err();
+        assert!(err.to_string().contains("Custom"), "{}", err);
+
+        let recipe = Recipe(vec![Action::Dilute, action]);
+        let json = serde_json::to_string(&recipe).unwrap();
+        assert_eq!(json, "\"WP\"");
+        assert!(serde_json::from_str::<Recipe>(&json).is_err());
+    }
+
+    #[test]
+    fn recipes_round_trip() {
+        let recipe: Recipe = "WEFBCSNWS".parse().unwrap();
+        assert_eq!(recipe.0.len(), 9);
+        assert_eq!(
+            recipe.0[1],
+            Action::AddIngredient(PotionIngredient::SpiderEye)
+        );
+        assert_eq!(recipe.to_string(), "WEFBCSNWS");
+    }
+
+    #[test]
+    fn empty_recipe_is_water() {
+        let recipe: Recipe = "".parse().unwrap();
+        assert_eq!(recipe, Recipe::default());
+        assert_eq!(recipe.apply(LiquidData(42)), LiquidData(42));
+    }
+
+    #[test]
+    fn apply_matches_single_steps() {
+        let recipe: Recipe = "WEFBCSNWS".parse().unwrap();
+        assert_eq!(recipe.apply(LiquidData::default()), LiquidData(20485));
+    }
+
+    #[test]
+    fn states_follow_each_step() {
+        let recipe: Recipe = "WEN".parse().unwrap();
+        let states: Vec<_> = recipe.states(LiquidData::default()).collect();
+        assert_eq!(
+            states,
+            vec![
+                (Action::Dilute, LiquidData(0)),
+                (
+                    Action::AddIngredient(PotionIngredient::SpiderEye),
+                    LiquidData(1184)
+                ),
+                (Action::AddNetherWart, LiquidData(1088)),
+            ]
+        );
+        assert_eq!(
+            states.last().unwrap().1,
+            recipe.apply(LiquidData::default())
+        );
+    }
+
+    #[test]
+    fn simplify_keeps_the_result() {
+        let start = WATER.liquid;
+        let recipe: Recipe = "WWEEN".parse().unwrap();
+        assert_eq!(recipe.simplify(start).to_string(), "EN");
+        // a detour through sugar and back by diluting
+        let recipe: Recipe = "ESWSNSW".parse().unwrap();
+        let simplified = recipe.simplify(start);
+        assert!(simplified.0.len() < recipe.0.len());
+        assert_eq!(simplified.apply(start), recipe.apply(start));
+        for fixture in fixtures::ALL.iter() {
+            if let Some(letters) = fixture.recipe {
+                let shortest: Recipe = letters.parse().unwrap();
+                assert_eq!(shortest.simplify(start), shortest);
+                let padded: Recipe = format!("W{}W{}", letters, letters).parse().unwrap();
+                let simplified = padded.simplify(start);
+                assert_eq!(simplified.apply(start), padded.apply(start));
+                assert!(simplified.0.len() <= padded.0.len());
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_letters_are_rejected() {
+        assert_eq!(
+            "WEx".parse::<Recipe>(),
+            Err(ParseRecipeError {
+                position: 2,
+                letter: 'x'
+            })
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! Letters for custom ingredients, so that recipes using them can be written and read.
+
+use crate::solver::RecipeTable;
+use crate::{Action, CustomIngredient, LiquidData, ParseRecipeError, PotionIngredient, Recipe};
+use std::error::Error;
+use std::fmt;
+
+/// The custom ingredients a server has, usually next to the ones the game has.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct IngredientRegistry {
+    /// Whether the ingredients in `PotionIngredient::ALL` can be used.
+    builtins: bool,
+    ingredients: Vec<CustomIngredient>,
+}
+
+/// The error returned when an ingredient's letter is already used by another action.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct RegisterError {
+    pub letter: char,
+}
+
+impl fmt::Display for RegisterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the letter '{}' is already used", self.letter)
+    }
+}
+
+impl Error for RegisterError {}
+
+impl IngredientRegistry {
+    /// A registry with only the ingredients the game has.
+    pub fn new() -> Self {
+        IngredientRegistry {
+            builtins: true,
+            ingredients: Vec::new(),
+        }
+    }
+
+    /// A registry without the ingredients the game has, for servers that change all of them.
+    /// Water and nether wart are still there, but the letters of the ingredients are free.
+    pub fn without_builtins() -> Self {
+        IngredientRegistry {
+            builtins: false,
+            ingredients: Vec::new(),
+        }
+    }
+
+    /// Adds an ingredient and returns the action that adds it to a cauldron.
+    pub fn register(&mut self, ingredient: CustomIngredient) -> Result<Action, RegisterError> {
+        let letter = ingredient.letter();
+        if self.action(letter).is_some() {
+            return Err(RegisterError { letter });
+        }
+        self.ingredients.push(ingredient);
+        Ok(Action::AddIngredient(PotionIngredient::Custom(ingredient)))
+    }
+
+    /// The custom ingredients, in the order they were registered.
+    pub fn ingredients(&self) -> &[CustomIngredient] {
+        &self.ingredients
+    }
+
+    /// Finds the action for a letter, including the letters of custom ingredients.
+    pub fn action(&self, letter: char) -> Option<Action> {
+        let builtin = Action::from_letter(letter).filter(|&a| self.is_available(a));
+        builtin.or_else(|| {
+            self.ingredients
+                .iter()
+                .find(|i| i.letter() == letter)
+                .map(|&i| Action::AddIngredient(PotionIngredient::Custom(i)))
+        })
+    }
+
+    /// Every action, those in `Action::ALL` that are available followed by the custom ingredients.
+    pub fn actions(&self) -> Vec<Action> {
+        let custom = self
+            .ingredients
+            .iter()
+            .map(|&i| Action::AddIngredient(PotionIngredient::Custom(i)));
+        Action::ALL
+            .iter()
+            .copied()
+            .filter(|&a| self.is_available(a))
+            .chain(custom)
+            .collect()
+    }
+
+    /// Whether `action` can be taken, which is only not the case for the ingredients of the game
+    /// in a registry without them.
+    pub fn is_available(&self, action: Action) -> bool {
+        match action {
+            Action::AddIngredient(PotionIngredient::Custom(i)) => self.ingredients.contains(&i),
+            Action::AddIngredient(_) => self.builtins,
+            Action::Dilute | Action::AddNetherWart => true,
+        }
+    }
+
+    /// Parses a recipe like `Recipe::from_str`, also accepting the custom ingredients.
+    pub fn parse_recipe(&self, s: &str) -> Result<Recipe, ParseRecipeError> {
+        s.chars()
+            .enumerate()
+            .map(|(position, letter)| {
+                self.action(letter)
+                    .ok_or(ParseRecipeError { position, letter })
+            })
+            .collect::<Result<_, _>>()
+            .map(Recipe)
+    }
+
+    /// Finds the shortest recipes from `start` with every action, custom ingredients included.
+    pub fn recipe_table(&self, start: LiquidData) -> RecipeTable {
+        RecipeTable::build_using(start, &self.actions())
+    }
+}
+
+impl Default for IngredientRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IngredientRegistry, RegisterError};
+    use crate::{Action, CustomIngredient, LiquidData, PotionIngredient};
+
+    const PIXIE_DUST: CustomIngredient = CustomIngredient::new("Pixie Dust", 'P', &[2, 12]);
+
+    #[test]
+    fn letters_must_be_unused() {
+        let mut registry = IngredientRegistry::new();
+        let action = registry.register(PIXIE_DUST).unwrap();
+        assert_eq!(registry.action('P'), Some(action));
+        assert_eq!(
+            registry.register(CustomIngredient::new("Salt", 'S', &[3])),
+            Err(RegisterError { letter: 'S' })
+        );
+        assert_eq!(
+            registry.register(CustomIngredient::new("Pepper", 'P', &[4])),
+            Err(RegisterError { letter: 'P' })
+        );
+        assert_eq!(registry.ingredients(), [PIXIE_DUST]);
+        assert_eq!(registry.actions().len(), 9);
+    }
+
+    #[test]
+    fn recipes_can_use_custom_ingredients() {
+        let mut registry = IngredientRegistry::new();
+        let pixie_dust = registry.register(PIXIE_DUST).unwrap();
+        let recipe = registry.parse_recipe("SPN").unwrap();
+        assert_eq!(recipe.0[1], pixie_dust);
+        assert_eq!(recipe.to_string(), "SPN");
+        assert!("SPN".parse::<crate::Recipe>().is_err());
+
+        // bits 2 and 12 can't be set by any built-in ingredient, nor by wart from water
+        let target = LiquidData(1 << 2 | 1 << 12);
+        assert_eq!(crate::solver::breadth_first(target), None);
+        let table = registry.recipe_table(LiquidData::default());
+        assert_eq!(table.recipe(target).unwrap().0, vec![pixie_dust]);
+        assert!(table.len() > 5339);
+        assert_eq!(
+            recipe
+                .materials()
+                .ingredient(PotionIngredient::Custom(PIXIE_DUST)),
+            1
+        );
+        assert!(Action::ALL
+            .iter()
+            .all(|a| registry.action(a.letter()) == Some(*a)));
+    }
+
+    #[test]
+    fn builtin_ingredients_can_be_replaced() {
+        let mut registry = IngredientRegistry::without_builtins();
+        let sugar = CustomIngredient::new("Sugar", 'S', &[3]);
+        let action = registry.register(sugar).unwrap();
+        assert_eq!(registry.parse_recipe("SWN").unwrap().0[0], action);
+        assert_eq!(
+            registry.actions(),
+            [Action::Dilute, Action::AddNetherWart, action]
+        );
+        assert!(!registry.is_available(Action::AddIngredient(PotionIngredient::SpiderEye)));
+        assert!(registry.parse_recipe("E").is_err());
+    }
+}
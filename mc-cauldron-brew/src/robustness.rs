@@ -0,0 +1,110 @@
+//! How well a recipe survives a player's slip, for guides that want to recommend forgiving ones.
+
+use crate::{LiquidData, Recipe};
+
+/// What happens to the result of a recipe if one step is left out or taken twice, see
+/// `Recipe::robustness`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Robustness {
+    /// The number of slips that were tried: leaving out each step, and taking each step twice.
+    pub mistakes: usize,
+    /// How many of them still brew the same liquid.
+    pub same_liquid: usize,
+    /// How many of them still brew a liquid with the same effects, strengths and durations, so
+    /// that nobody would notice when drinking it. This includes those with the same liquid.
+    pub same_effects: usize,
+}
+
+impl Robustness {
+    /// The share of slips that still give the same effects, 1 for a recipe without steps.
+    pub fn score(&self) -> f64 {
+        if self.mistakes == 0 {
+            1.0
+        } else {
+            self.same_effects as f64 / self.mistakes as f64
+        }
+    }
+}
+
+impl Recipe {
+    /// Tries every way to get one step wrong, by leaving it out or taking it twice, and counts
+    /// how many of them still brew what this recipe brews from `start`.
+    pub fn robustness(&self, start: LiquidData) -> Robustness {
+        let target = self.apply(start);
+        let effects = target.effect_details();
+        let mut robustness = Robustness {
+            mistakes: 0,
+            same_liquid: 0,
+            same_effects: 0,
+        };
+        for i in 0..self.0.len() {
+            let mut dropped = self.0.clone();
+            dropped.remove(i);
+            let mut doubled = self.0.clone();
+            doubled.insert(i, self.0[i]);
+            for actions in [dropped, doubled].iter() {
+                let result = start.apply_all(actions.iter().copied());
+                robustness.mistakes += 1;
+                if result == target {
+                    robustness.same_liquid += 1;
+                }
+                if result == target || result.effect_details() == effects {
+                    robustness.same_effects += 1;
+                }
+            }
+        }
+        robustness
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Robustness;
+    use crate::fixtures::{self, WATER};
+    use crate::{Action, Recipe};
+
+    #[test]
+    fn spider_eye_survives_being_doubled() {
+        let recipe: Recipe = "E".parse().unwrap();
+        let robustness = recipe.robustness(WATER.liquid);
+        assert_eq!(
+            robustness,
+            Robustness {
+                mistakes: 2,
+                same_liquid: 1,
+                same_effects: 1,
+            }
+        );
+        assert_eq!(robustness.score(), 0.5);
+        assert_eq!(Recipe::default().robustness(WATER.liquid).score(), 1.0);
+    }
+
+    #[test]
+    fn liquids_without_effects_are_alike() {
+        // leaving out the sugar gives water, which has no effects either
+        let recipe: Recipe = "S".parse().unwrap();
+        let robustness = recipe.robustness(WATER.liquid);
+        assert_eq!(robustness.same_liquid, 1);
+        assert_eq!(robustness.same_effects, 2);
+    }
+
+    #[test]
+    fn counts_stay_within_the_mistakes() {
+        for fixture in fixtures::ALL.iter() {
+            if let Some(letters) = fixture.recipe {
+                let recipe: Recipe = letters.parse().unwrap();
+                let robustness = recipe.robustness(WATER.liquid);
+                assert_eq!(robustness.mistakes, 2 * recipe.0.len());
+                assert!(robustness.same_liquid <= robustness.same_effects);
+                assert!(robustness.same_effects <= robustness.mistakes);
+                // ingredients and diluting do nothing the second time
+                let idempotent = recipe
+                    .0
+                    .iter()
+                    .filter(|&&a| a != Action::AddNetherWart)
+                    .count();
+                assert!(robustness.same_liquid >= idempotent);
+            }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! A cauldron that remembers how it got where it is, for frontends that let players experiment.
+
+use crate::{Action, LiquidData, Recipe};
+
+/// The actions taken on a cauldron so far, with undo and redo.
+///
+/// Undoing doesn't need to invert anything: the liquid is brewed again from the start with the
+/// remaining actions.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct CauldronSession {
+    start: LiquidData,
+    actions: Vec<Action>,
+    /// Actions that were undone, the most recently undone last.
+    undone: Vec<Action>,
+    current: LiquidData,
+}
+
+impl CauldronSession {
+    /// Starts a session with a cauldron holding `start`.
+    pub fn new(start: LiquidData) -> Self {
+        CauldronSession {
+            start,
+            actions: Vec::new(),
+            undone: Vec::new(),
+            current: start,
+        }
+    }
+
+    /// Takes an action and returns the new liquid. This forgets the actions that could be redone.
+    pub fn apply(&mut self, action: Action) -> LiquidData {
+        self.actions.push(action);
+        self.undone.clear();
+        self.current = action.apply_to(self.current);
+        self.current
+    }
+
+    /// Takes back the last action and returns it, or `None` if there is nothing to undo.
+    pub fn undo(&mut self) -> Option<Action> {
+        let action = self.actions.pop()?;
+        self.undone.push(action);
+        self.current = self.start.apply_all(self.actions.iter().copied());
+        Some(action)
+    }
+
+    /// Takes the last undone action again and returns it, or `None` if there is nothing to redo.
+    pub fn redo(&mut self) -> Option<Action> {
+        let action = self.undone.pop()?;
+        self.actions.push(action);
+        self.current = action.apply_to(self.current);
+        Some(action)
+    }
+
+    /// Goes back to the start, which can't be undone.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.start);
+    }
+
+    /// What the cauldron holds now.
+    pub fn current(&self) -> LiquidData {
+        self.current
+    }
+
+    /// What the cauldron held before the last action, or `None` if none was taken.
+    pub fn previous(&self) -> Option<LiquidData> {
+        let (_, before) = self.actions.split_last()?;
+        Some(self.start.apply_all(before.iter().copied()))
+    }
+
+    /// What the cauldron held when the session started.
+    pub fn start(&self) -> LiquidData {
+        self.start
+    }
+
+    /// The actions taken since the start, in order.
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    /// Whether there is an action to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.actions.is_empty()
+    }
+
+    /// Whether there is an undone action to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// The actions taken since the start, as a recipe.
+    pub fn to_recipe(&self) -> Recipe {
+        Recipe(self.actions.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CauldronSession;
+    use crate::fixtures::{SPIDER_EYE, SPIDER_EYE_WART, SWIFTNESS};
+    use crate::{Action, Recipe};
+
+    #[test]
+    fn undo_and_redo_retrace_the_actions() {
+        let mut session = CauldronSession::default();
+        let recipe: Recipe = "WEN".parse().unwrap();
+        for &action in &recipe.0 {
+            session.apply(action);
+        }
+        assert_eq!(session.current(), SPIDER_EYE_WART.liquid);
+        assert_eq!(session.previous(), Some(SPIDER_EYE.liquid));
+        assert_eq!(session.undo(), Some(Action::AddNetherWart));
+        assert_eq!(session.current(), SPIDER_EYE.liquid);
+        assert_eq!(session.redo(), Some(Action::AddNetherWart));
+        assert_eq!(session.current(), SPIDER_EYE_WART.liquid);
+        assert_eq!(session.redo(), None);
+        assert_eq!(session.to_recipe(), recipe);
+    }
+
+    #[test]
+    fn new_actions_forget_the_redo_history() {
+        let mut session = CauldronSession::new(SWIFTNESS.liquid);
+        session.apply(Action::AddNetherWart);
+        session.undo();
+        assert!(session.can_redo());
+        session.apply(Action::Dilute);
+        assert!(!session.can_redo());
+        assert_eq!(session.current(), SWIFTNESS.liquid.dilute());
+        session.reset();
+        assert_eq!(session.current(), SWIFTNESS.liquid);
+        assert!(!session.can_undo());
+    }
+}
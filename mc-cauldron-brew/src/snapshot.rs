@@ -0,0 +1,176 @@
+//! Snapshots of `apply_wart` and of the shortest recipes over every 15-bit liquid.
+//!
+//! The wart automaton and the searches are the parts of the crate whose output can't be checked
+//! by eye, so their complete tables are kept in `snapshots/`. Run the tests with
+//! `UPDATE_SNAPSHOTS=1` to rewrite them after an intentional change and review the diff like any
+//! other.
+
+use crate::solver::RecipeTable;
+use crate::{LiquidData, Recipe};
+use std::fmt::Write;
+
+const WART_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/snapshots/apply_wart.txt");
+const RECIPES_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/snapshots/recipe_table.txt");
+
+/// Number of entries written on each line of the wart snapshot.
+const PER_LINE: usize = 16;
+
+/// One entry per dv in a snapshot, and how the file stores them.
+trait Entry: PartialEq + Sized {
+    fn format_table(table: &[Self]) -> String;
+    fn parse_table(s: &str) -> Vec<Self>;
+    /// The entry in the report of a mismatch.
+    fn show(&self) -> String;
+    /// The bytes that are hashed for this entry.
+    fn bytes(&self) -> Vec<u8>;
+}
+
+/// The wart table is laid out like a hex dump: the first dv of each line, followed by the results.
+impl Entry for u16 {
+    fn format_table(table: &[Self]) -> String {
+        let mut out = String::new();
+        for (i, line) in table.chunks(PER_LINE).enumerate() {
+            write!(out, "{:04x}:", i * PER_LINE).unwrap();
+            for v in line {
+                write!(out, " {:04x}", v).unwrap();
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    fn parse_table(s: &str) -> Vec<Self> {
+        s.lines()
+            .flat_map(|line| line.split_whitespace().skip(1))
+            .map(|v| u16::from_str_radix(v, 16).expect("snapshot entries are hex"))
+            .collect()
+    }
+
+    fn show(&self) -> String {
+        format!("{:05}", self)
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        self.to_le_bytes().to_vec()
+    }
+}
+
+/// The recipe table has a line with the dv and the recipe for every liquid that can be brewed, the
+/// others are left out.
+impl Entry for Option<Recipe> {
+    fn format_table(table: &[Self]) -> String {
+        let mut out = String::new();
+        for (dv, recipe) in table.iter().enumerate() {
+            if let Some(recipe) = recipe {
+                writeln!(out, "{:05} {}", dv, recipe).unwrap();
+            }
+        }
+        out
+    }
+
+    fn parse_table(s: &str) -> Vec<Self> {
+        let mut table = vec![None; 0x8000];
+        for line in s.lines() {
+            let (dv, recipe) = line.split_once(' ').unwrap_or((line, ""));
+            let dv: usize = dv.parse().expect("snapshot lines start with a dv");
+            table[dv] = Some(recipe.parse().expect("snapshot recipes are valid"));
+        }
+        table
+    }
+
+    fn show(&self) -> String {
+        match self {
+            Some(recipe) if recipe.0.is_empty() => "(nothing)".to_string(),
+            Some(recipe) => recipe.to_string(),
+            None => "(unreachable)".to_string(),
+        }
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        match self {
+            Some(recipe) => format!("{}\n", recipe).into_bytes(),
+            None => vec![b'-'],
+        }
+    }
+}
+
+/// FNV-1a, so that a mismatch can be identified at a glance.
+fn hash<T: Entry>(table: &[T]) -> u64 {
+    table
+        .iter()
+        .flat_map(|v| v.bytes())
+        .fold(0xcbf2_9ce4_8422_2325, |h, b| {
+            (h ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+        })
+}
+
+/// Compares `actual` with the snapshot at `path`, or rewrites it with `UPDATE_SNAPSHOTS`.
+fn check_snapshot<T: Entry>(what: &str, path: &str, actual: &[T]) {
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(path, T::format_table(actual)).expect("failed to write snapshot");
+        return;
+    }
+
+    let expected = T::parse_table(
+        &std::fs::read_to_string(path)
+            .expect("snapshot missing, run with UPDATE_SNAPSHOTS=1 to create it"),
+    );
+    assert_eq!(expected.len(), actual.len(), "snapshot has the wrong size");
+    if hash(&expected) == hash(actual) {
+        return;
+    }
+
+    let diffs: Vec<_> = expected
+        .iter()
+        .zip(actual.iter())
+        .enumerate()
+        .filter(|(_, (e, a))| e != a)
+        .collect();
+    let mut report = String::new();
+    for (dv, (e, a)) in diffs.iter().take(32) {
+        writeln!(report, "  {:05}: {} -> {}", dv, e.show(), a.show()).unwrap();
+    }
+    panic!(
+        "{} changed for {} of {} values (hash {:016x} -> {:016x}):\n{}",
+        what,
+        diffs.len(),
+        actual.len(),
+        hash(&expected),
+        hash(actual),
+        report
+    );
+}
+
+/// Calculates the current wart table.
+fn wart_table() -> Vec<u16> {
+    (0..32768u16)
+        .map(|v| LiquidData(v).apply_wart().0)
+        .collect()
+}
+
+/// Calculates the shortest recipe for every dv.
+fn recipe_table() -> Vec<Option<Recipe>> {
+    let table = RecipeTable::build();
+    LiquidData::all().map(|ld| table.recipe(ld)).collect()
+}
+
+#[test]
+fn apply_wart_matches_snapshot() {
+    check_snapshot("apply_wart", WART_PATH, &wart_table());
+}
+
+#[test]
+fn recipe_table_matches_snapshot() {
+    check_snapshot("RecipeTable::build", RECIPES_PATH, &recipe_table());
+}
+
+#[test]
+fn format_round_trips() {
+    let table = wart_table();
+    assert_eq!(u16::parse_table(&u16::format_table(&table)), table);
+    let recipes = recipe_table();
+    assert_eq!(
+        Option::<Recipe>::parse_table(&Option::<Recipe>::format_table(&recipes)),
+        recipes
+    );
+}
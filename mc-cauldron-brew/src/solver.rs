@@ -0,0 +1,1355 @@
+//! Searches for recipes.
+
+use crate::{
+    effect_classes, Action, EffectMask, LiquidData, PotionEffect, Recipe, TransitionTable,
+};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+mod bottling;
+mod checkpoint;
+mod enumerate;
+mod lookup;
+
+pub use self::bottling::{plan_bottling, BottlingPlan, PlanError, MAX_TARGETS};
+pub use self::enumerate::{recipes_up_to, RecipesUpTo};
+pub use self::lookup::{is_lookup_table, lookup, MAX_STEPS};
+
+/// The best recipe for every liquid that can be brewed from some starting liquid, usually water.
+///
+/// Depending on how the table was built, that is either the shortest or the cheapest recipe.
+#[derive(Clone, Debug)]
+pub struct RecipeTable {
+    /// visits[dv] = how the recipe for the liquid with that dv reaches it.
+    visits: Vec<Option<Visit>>,
+}
+
+impl RecipeTable {
+    /// Finds the shortest recipes with a breadth-first search from water.
+    ///
+    /// Where a liquid has several shortest recipes, the table has the first of them in recipe order
+    /// (`SGEFBCWN`), so the result doesn't depend on how the search is carried out.
+    pub fn build() -> Self {
+        Self::build_from(LiquidData::default())
+    }
+
+    /// Finds the shortest recipes with a breadth-first search from `start`, e.g. a cauldron that
+    /// is already partly brewed.
+    ///
+    /// # Panics
+    ///
+    /// If `start` has any bit above the 15 the game uses set.
+    pub fn build_from(start: LiquidData) -> Self {
+        Self::build_with_progress(start, |_| {})
+    }
+
+    /// Like `RecipeTable::build_from`, but also measures the search.
+    pub fn build_with_stats(start: LiquidData) -> (Self, SearchStats) {
+        let mut stats = SearchStats::start();
+        let table = Self::build_with_progress(start, |progress| stats.record(progress));
+        (table, stats)
+    }
+
+    /// Like `RecipeTable::build_from`, but calls `on_level` after every level of the search.
+    pub fn build_with_progress(start: LiquidData, on_level: impl FnMut(Progress)) -> Self {
+        assert!(start.0 < 0x8000, "{} is not a valid liquid", start.0);
+        #[cfg(feature = "rayon")]
+        let visits = parallel::search_breadth_first(start, on_level);
+        #[cfg(not(feature = "rayon"))]
+        let visits =
+            search_breadth_first(start, &Action::ALL, Action::apply_to, |_| false, on_level).0;
+        RecipeTable { visits }
+    }
+
+    /// Finds the shortest recipes from `start` that only take `actions`, e.g. to see what can be
+    /// brewed without some ingredient.
+    ///
+    /// # Panics
+    ///
+    /// If `start` has any bit above the 15 the game uses set.
+    pub fn build_using(start: LiquidData, actions: &[Action]) -> Self {
+        Self::build_restricted(start, actions, Action::apply_to)
+    }
+
+    /// Like `RecipeTable::build_using`, but looks every step up in `transitions`, which saves
+    /// time when building many tables.
+    pub fn build_with_transitions(
+        transitions: &TransitionTable,
+        start: LiquidData,
+        actions: &[Action],
+    ) -> Self {
+        Self::build_restricted(start, actions, |action, ld| transitions.apply(action, ld))
+    }
+
+    fn build_restricted(
+        start: LiquidData,
+        actions: &[Action],
+        step: impl Fn(Action, LiquidData) -> LiquidData,
+    ) -> Self {
+        assert!(start.0 < 0x8000, "{} is not a valid liquid", start.0);
+        let mut actions = actions.to_vec();
+        actions.sort();
+        actions.dedup();
+        let visits = search_breadth_first(start, &actions, step, |_| false, |_| {}).0;
+        RecipeTable { visits }
+    }
+
+    /// Finds the cheapest recipes with Dijkstra's algorithm from water.
+    ///
+    /// Among recipes of equal cost, the one with fewer steps is preferred.
+    pub fn cheapest(costs: &ActionCosts) -> Self {
+        let mut search = CheapestSearch::new(costs);
+        search.run(usize::MAX);
+        search.into_table()
+    }
+
+    /// Finds the cheapest recipes from `start` that only take `actions`, with Dijkstra's algorithm.
+    /// Unlike `ActionCosts`, `cost` can give custom ingredients a cost of their own.
+    ///
+    /// Among recipes of equal cost, the one with fewer steps is preferred.
+    ///
+    /// # Panics
+    ///
+    /// If `start` has any bit above the 15 the game uses set.
+    pub fn cheapest_using(
+        start: LiquidData,
+        actions: &[Action],
+        cost: impl Fn(Action) -> u32,
+    ) -> Self {
+        let mut search = CheapestSearch::using(start, actions, cost);
+        search.run(usize::MAX);
+        search.into_table()
+    }
+
+    /// Looks up the recipe for a liquid, if it can be brewed at all.
+    ///
+    /// Only the last step of every recipe is stored, so this follows those steps back to the start.
+    pub fn recipe(&self, target: LiquidData) -> Option<Recipe> {
+        self.visits.get(target.0 as usize)?.as_ref()?;
+        Some(Recipe(trace(&self.visits, target)))
+    }
+
+    /// Picks one liquid from every class of liquids in the table with the same effects (see
+    /// `effect_classes`): the one whose recipe is cheapest with `costs`, then shortest, then
+    /// first in recipe order.
+    pub fn cheapest_per_class(&self, costs: &impl CostFunction) -> Vec<(LiquidData, Recipe)> {
+        effect_classes(self.iter().map(|(ld, _)| ld))
+            .into_iter()
+            .filter_map(|class| {
+                class
+                    .liquids
+                    .into_iter()
+                    .filter_map(|ld| Some((ld, self.recipe(ld)?)))
+                    .min_by_key(|(_, recipe)| {
+                        let cost: u64 = recipe.0.iter().map(|&a| costs.cost(a) as u64).sum();
+                        (cost, recipe.0.len(), recipe.clone())
+                    })
+            })
+            .collect()
+    }
+
+    /// The last step of the recipe for a liquid: the liquid before it and the action taken there.
+    ///
+    /// Returns `None` for the start and for liquids that can't be brewed.
+    pub fn predecessor(&self, target: LiquidData) -> Option<(LiquidData, Action)> {
+        match self.visits.get(target.0 as usize)? {
+            Some(Visit::Step(prev, action)) => Some((*prev, *action)),
+            _ => None,
+        }
+    }
+
+    /// Iterates over all liquids that can be brewed, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = (LiquidData, Recipe)> + '_ {
+        LiquidData::all().filter_map(move |ld| Some((ld, self.recipe(ld)?)))
+    }
+
+    /// The number of liquids that can be brewed.
+    pub fn len(&self) -> usize {
+        self.visits.iter().filter(|v| v.is_some()).count()
+    }
+
+    /// Whether no liquid can be brewed, which is never the case since the start is always there.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The length of the longest recipe in the table.
+    pub fn max_steps(&self) -> usize {
+        self.iter().map(|(_, r)| r.0.len()).max().unwrap_or(0)
+    }
+}
+
+/// How far a breadth-first search has come.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Progress {
+    /// The number of steps of the recipes found in the level that was just finished.
+    pub depth: usize,
+    /// How many liquids have been found so far, including the start.
+    pub discovered: usize,
+    /// How many liquids the next level starts from.
+    pub frontier: usize,
+}
+
+/// Measurements of a breadth-first search, one entry per level.
+#[derive(Clone, Debug)]
+pub struct SearchStats {
+    pub levels: Vec<LevelStats>,
+    last: Progress,
+    last_time: Instant,
+}
+
+/// Measurements of one level of a breadth-first search.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct LevelStats {
+    /// The number of steps of the recipes found in this level.
+    pub depth: usize,
+    /// How many liquids the level started from, i.e. the size of the queue.
+    pub expanded: usize,
+    /// How many new liquids the level found.
+    pub discovered: usize,
+    /// The wall time the level took.
+    pub time: Duration,
+}
+
+impl SearchStats {
+    /// Starts measuring a search that is about to begin.
+    pub fn start() -> Self {
+        SearchStats {
+            levels: Vec::new(),
+            last: Progress {
+                depth: 0,
+                discovered: 1,
+                frontier: 1,
+            },
+            last_time: Instant::now(),
+        }
+    }
+
+    /// Records a level reported by `RecipeTable::build_with_progress`.
+    pub fn record(&mut self, progress: Progress) {
+        let now = Instant::now();
+        self.levels.push(LevelStats {
+            depth: progress.depth,
+            expanded: self.last.frontier,
+            discovered: progress.discovered - self.last.discovered,
+            time: now - self.last_time,
+        });
+        self.last = progress;
+        self.last_time = now;
+    }
+
+    /// The wall time of the whole search.
+    pub fn total_time(&self) -> Duration {
+        self.levels.iter().map(|level| level.time).sum()
+    }
+
+    /// The most liquids any level started from.
+    pub fn peak_queue(&self) -> usize {
+        self.levels
+            .iter()
+            .map(|level| level.expanded)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The effective branching factor: the `b` for which `b^d` is the number of liquids found,
+    /// where `d` is the number of steps of the longest recipe. It is 0 if nothing was found.
+    ///
+    /// It says how many new liquids each step opens up on average, regardless of how many
+    /// successors are generated and thrown away because they were seen before.
+    pub fn branching_factor(&self) -> f64 {
+        let depth = self
+            .levels
+            .iter()
+            .filter(|level| level.discovered > 0)
+            .map(|level| level.depth)
+            .max()
+            .unwrap_or(0);
+        let discovered: usize = self.levels.iter().map(|level| level.discovered).sum();
+        if depth == 0 {
+            0.0
+        } else {
+            (discovered as f64).powf(1.0 / depth as f64)
+        }
+    }
+}
+
+/// What a search for the cheapest recipes charges for each action: either `ActionCosts`, or a
+/// function of the action, which can also price custom ingredients.
+pub trait CostFunction {
+    fn cost(&self, action: Action) -> u32;
+}
+
+impl CostFunction for ActionCosts {
+    fn cost(&self, action: Action) -> u32 {
+        ActionCosts::cost(self, action)
+    }
+}
+
+impl<F: Fn(Action) -> u32> CostFunction for F {
+    fn cost(&self, action: Action) -> u32 {
+        self(action)
+    }
+}
+
+/// A search for the cheapest recipes that can be interrupted, and continued later.
+///
+/// Running it to the end gives the same table as `RecipeTable::cheapest` or
+/// `RecipeTable::cheapest_using`. Searches with `ActionCosts` over every built-in action can also
+/// be saved, see `CheapestSearch::write_to`.
+#[derive(Clone, Debug)]
+pub struct CheapestSearch<C = ActionCosts> {
+    costs: C,
+    /// The actions the search takes, in recipe order
+    actions: Vec<Action>,
+    /// (cost, steps) of the best known recipe for each dv
+    best: Vec<Option<(u64, u32)>>,
+    visits: Vec<Option<Visit>>,
+    heap: BinaryHeap<Reverse<(u64, u32, u16)>>,
+}
+
+impl CheapestSearch {
+    /// Starts a search from water with every built-in action.
+    pub fn new(costs: &ActionCosts) -> Self {
+        Self::using(LiquidData::default(), &Action::ALL, *costs)
+    }
+}
+
+impl<C: CostFunction> CheapestSearch<C> {
+    /// Starts a search from `start` that only takes `actions`.
+    ///
+    /// # Panics
+    ///
+    /// If `start` has any bit above the 15 the game uses set.
+    pub fn using(start: LiquidData, actions: &[Action], costs: C) -> Self {
+        assert!(start.0 < 0x8000, "{} is not a valid liquid", start.0);
+        let mut actions = actions.to_vec();
+        actions.sort();
+        actions.dedup();
+        let mut search = CheapestSearch {
+            costs,
+            actions,
+            best: vec![None; 0x8000],
+            visits: vec![None; 0x8000],
+            heap: BinaryHeap::new(),
+        };
+        search.best[start.0 as usize] = Some((0, 0));
+        search.visits[start.0 as usize] = Some(Visit::Root);
+        search.heap.push(Reverse((0, 0, start.0)));
+        search
+    }
+
+    /// Settles up to `limit` more liquids and returns whether the search is finished.
+    ///
+    /// Among recipes of equal cost, the one with fewer steps is preferred.
+    pub fn run(&mut self, limit: usize) -> bool {
+        let mut settled = 0;
+        while settled < limit {
+            let Reverse((cost, steps, dv)) = match self.heap.pop() {
+                Some(entry) => entry,
+                None => break,
+            };
+            if self.best[dv as usize] != Some((cost, steps)) {
+                // already reached more cheaply
+                continue;
+            }
+            settled += 1;
+            for &action in &self.actions {
+                let next = action.apply_to(LiquidData(dv));
+                let candidate = (cost + self.costs.cost(action) as u64, steps + 1);
+                if self.best[next.0 as usize].is_none_or(|known| candidate < known) {
+                    self.best[next.0 as usize] = Some(candidate);
+                    self.visits[next.0 as usize] = Some(Visit::Step(LiquidData(dv), action));
+                    self.heap.push(Reverse((candidate.0, candidate.1, next.0)));
+                }
+            }
+        }
+        self.is_finished()
+    }
+
+    /// Whether every liquid that can be brewed has its cheapest recipe.
+    pub fn is_finished(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// The costs this search uses.
+    pub fn costs(&self) -> &C {
+        &self.costs
+    }
+
+    /// The actions this search takes, in recipe order.
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    /// The recipes found so far. Unless the search is finished, some of them may not be the
+    /// cheapest yet and some liquids may be missing.
+    pub fn into_table(self) -> RecipeTable {
+        RecipeTable {
+            visits: self.visits,
+        }
+    }
+}
+
+/// What each action costs, e.g. to reflect how rare its ingredient is.
+///
+/// Only the actions in `Action::ALL` have a cost of their own; custom ingredients always cost 1.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ActionCosts([u32; 8]);
+
+impl ActionCosts {
+    /// Every action costs 1, so the cheapest recipes are the shortest.
+    pub fn uniform() -> Self {
+        ActionCosts([1; 8])
+    }
+
+    /// Returns these costs with the cost of `action` replaced, or `None` if `action` adds a
+    /// custom ingredient, which can't have a cost of its own here.
+    pub fn with(mut self, action: Action, cost: u32) -> Option<Self> {
+        *self.0.get_mut(action.index())? = cost;
+        Some(self)
+    }
+
+    /// The cost of taking an action.
+    pub fn cost(&self, action: Action) -> u32 {
+        self.0.get(action.index()).copied().unwrap_or(1)
+    }
+
+    /// The total cost of a recipe.
+    pub fn recipe_cost(&self, recipe: &Recipe) -> u64 {
+        recipe.0.iter().map(|&a| self.cost(a) as u64).sum()
+    }
+}
+
+impl Default for ActionCosts {
+    fn default() -> Self {
+        Self::uniform()
+    }
+}
+
+/// Limits on how often each action may be taken in a recipe.
+///
+/// The default allows everything. Custom ingredients can't be limited.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct Constraints([Option<u32>; 8]);
+
+impl Constraints {
+    /// Returns these constraints with `action` not allowed at all, or `None` if `action` adds a
+    /// custom ingredient.
+    pub fn ban(self, action: Action) -> Option<Self> {
+        self.limit(action, 0)
+    }
+
+    /// Returns these constraints with `action` allowed at most `max` times, or `None` if `action`
+    /// adds a custom ingredient.
+    pub fn limit(mut self, action: Action, max: u32) -> Option<Self> {
+        *self.0.get_mut(action.index())? = Some(max);
+        Some(self)
+    }
+
+    /// How often `action` may be taken, if that is limited.
+    pub fn max_uses(&self, action: Action) -> Option<u32> {
+        self.0.get(action.index()).copied().flatten()
+    }
+
+    /// Whether a recipe stays within these limits.
+    pub fn allows(&self, recipe: &Recipe) -> bool {
+        Action::ALL.iter().all(|&action| {
+            self.max_uses(action)
+                .is_none_or(|max| recipe.0.iter().filter(|&&a| a == action).count() <= max as usize)
+        })
+    }
+}
+
+/// How a search reached a liquid.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum Visit {
+    /// Where the search started.
+    Root,
+    /// Reached from the other liquid via the action.
+    Step(LiquidData, Action),
+}
+
+/// Finds a shortest recipe from water to `target`.
+///
+/// This searches forward from water and backward from the target at the same time, always
+/// expanding the smaller of the two frontiers, until they meet. Backward steps use
+/// `LiquidData::preimages`, so the first call pays for indexing all nether wart results.
+pub fn bidirectional(target: LiquidData) -> Option<Recipe> {
+    let start = LiquidData::default();
+    if target.0 as usize >= 0x8000 {
+        return None;
+    }
+    if start == target {
+        return Some(Recipe::default());
+    }
+
+    // forward[dv]: the liquid reached before dv, backward[dv]: the liquid reached after dv
+    let mut forward: Vec<Option<Visit>> = vec![None; 0x8000];
+    let mut backward: Vec<Option<Visit>> = vec![None; 0x8000];
+    forward[start.0 as usize] = Some(Visit::Root);
+    backward[target.0 as usize] = Some(Visit::Root);
+    let mut forward_frontier = vec![start];
+    let mut backward_frontier = vec![target];
+
+    while !forward_frontier.is_empty() && !backward_frontier.is_empty() {
+        // Any meeting point found while expanding a complete level lies on a shortest path.
+        let mut meeting = None;
+        let mut next_frontier = Vec::new();
+        if forward_frontier.len() <= backward_frontier.len() {
+            for &ld in &forward_frontier {
+                for &action in Action::ALL.iter() {
+                    let next = action.apply_to(ld);
+                    if forward[next.0 as usize].is_none() {
+                        forward[next.0 as usize] = Some(Visit::Step(ld, action));
+                        next_frontier.push(next);
+                        if backward[next.0 as usize].is_some() {
+                            meeting = meeting.or(Some(next));
+                        }
+                    }
+                }
+            }
+            forward_frontier = next_frontier;
+        } else {
+            for &ld in &backward_frontier {
+                for &action in Action::ALL.iter() {
+                    for prev in ld.preimages(action) {
+                        if backward[prev.0 as usize].is_none() {
+                            backward[prev.0 as usize] = Some(Visit::Step(ld, action));
+                            next_frontier.push(prev);
+                            if forward[prev.0 as usize].is_some() {
+                                meeting = meeting.or(Some(prev));
+                            }
+                        }
+                    }
+                }
+            }
+            backward_frontier = next_frontier;
+        }
+
+        if let Some(meeting) = meeting {
+            return Some(join(&forward, &backward, meeting));
+        }
+    }
+    None
+}
+
+/// Follows the visits of a forward search back to its root and returns the actions taken.
+fn trace(visits: &[Option<Visit>], target: LiquidData) -> Vec<Action> {
+    let mut actions = Vec::new();
+    let mut current = target;
+    while let Some(Visit::Step(prev, action)) = visits[current.0 as usize] {
+        actions.push(action);
+        current = prev;
+    }
+    actions.reverse();
+    actions
+}
+
+/// Finds a shortest recipe from water to `target`, stopping the search as soon as it is found.
+///
+/// This is the same recipe that `RecipeTable::build` finds.
+pub fn breadth_first(target: LiquidData) -> Option<Recipe> {
+    breadth_first_from(LiquidData::default(), target)
+}
+
+/// Finds a shortest recipe from `start` to `target`, stopping the search as soon as it is found.
+pub fn breadth_first_from(start: LiquidData, target: LiquidData) -> Option<Recipe> {
+    if start.0 >= 0x8000 {
+        return None;
+    }
+    let (visits, found) = search_breadth_first(
+        start,
+        &Action::ALL,
+        Action::apply_to,
+        |ld| ld == target,
+        |_| {},
+    );
+    Some(Recipe(trace(&visits, found?)))
+}
+
+/// Finds the liquid with the shortest recipe among those that `accept` returns true for.
+pub fn first_matching(accept: impl FnMut(LiquidData) -> bool) -> Option<(LiquidData, Recipe)> {
+    let (visits, found) = search_breadth_first(
+        LiquidData::default(),
+        &Action::ALL,
+        Action::apply_to,
+        accept,
+        |_| {},
+    );
+    let found = found?;
+    Some((found, Recipe(trace(&visits, found))))
+}
+
+/// Finds the shortest recipe for a potion that has all `wanted` effects and none of the
+/// `forbidden` ones, along with the liquid it produces.
+pub fn with_effects(
+    wanted: &[PotionEffect],
+    forbidden: &[PotionEffect],
+) -> Option<(LiquidData, Recipe)> {
+    let wanted = EffectMask::from(wanted);
+    let forbidden = EffectMask::from(forbidden);
+    first_matching(|ld| {
+        let effects = ld.effect_mask();
+        wanted.is_subset(effects) && effects.is_disjoint(forbidden)
+    })
+}
+
+/// Searches breadth-first from `start` with `actions`, which must be in recipe order, either
+/// until `stop` returns true for a liquid or everything is explored. `step` takes an action.
+///
+/// Returns the visits so far, indexed by dv, and the liquid the search stopped at. `on_level` is
+/// called after every complete level.
+fn search_breadth_first(
+    start: LiquidData,
+    actions: &[Action],
+    step: impl Fn(Action, LiquidData) -> LiquidData,
+    mut stop: impl FnMut(LiquidData) -> bool,
+    mut on_level: impl FnMut(Progress),
+) -> (Vec<Option<Visit>>, Option<LiquidData>) {
+    let mut visits: Vec<Option<Visit>> = vec![None; 0x8000];
+    let mut queue: VecDeque<LiquidData> = VecDeque::new();
+
+    // Add the starting potion (usually plain water)
+    visits[start.0 as usize] = Some(Visit::Root);
+    queue.push_back(start);
+    if stop(start) {
+        return (visits, Some(start));
+    }
+
+    let mut progress = Progress {
+        depth: 0,
+        discovered: 1,
+        frontier: 1,
+    };
+    // The queue stays sorted by the recipes of its liquids: a liquid is first reached by the
+    // smallest recipe of the previous level followed by the smallest action, and added to the next
+    // queue in that order. So every liquid is reached by its smallest shortest recipe.
+    while !queue.is_empty() {
+        let mut next_queue = VecDeque::new();
+        // for every state in the queue
+        for prev_state in queue.into_iter() {
+            // check all possible actions to take from there
+            for action in actions {
+                let state = step(*action, prev_state);
+                // if that action leads to a new potion
+                if visits[state.0 as usize].is_none() {
+                    // remember how we got there and add it to the next queue
+                    visits[state.0 as usize] = Some(Visit::Step(prev_state, *action));
+                    progress.discovered += 1;
+                    next_queue.push_back(state);
+                    if stop(state) {
+                        return (visits, Some(state));
+                    }
+                }
+            }
+        }
+        queue = next_queue;
+        progress.depth += 1;
+        progress.frontier = queue.len();
+        on_level(progress);
+    }
+
+    (visits, None)
+}
+
+/// Finds a shortest recipe from water to `target` that stays within `constraints`.
+///
+/// Returns `None` if there is no such recipe, even if the target could be brewed without them.
+pub fn constrained(target: LiquidData, constraints: &Constraints) -> Option<Recipe> {
+    // A liquid together with how often each limited action was used to get there.
+    type State = (LiquidData, [u32; 8]);
+
+    let start: State = (LiquidData::default(), [0; 8]);
+    if target == start.0 {
+        return Some(Recipe::default());
+    }
+    let mut visits: HashMap<State, (State, Action)> = HashMap::new();
+    let mut queue = VecDeque::new();
+    queue.push_back(start);
+    while let Some(state) = queue.pop_front() {
+        for &action in Action::ALL.iter() {
+            let mut used = state.1;
+            if let Some(max) = constraints.max_uses(action) {
+                if used[action.index()] >= max {
+                    continue;
+                }
+                used[action.index()] += 1;
+            }
+            let next = (action.apply_to(state.0), used);
+            if next == start || visits.contains_key(&next) {
+                continue;
+            }
+            visits.insert(next, (state, action));
+            if next.0 == target {
+                let mut actions = Vec::new();
+                let mut current = next;
+                while let Some(&(prev, action)) = visits.get(&current) {
+                    actions.push(action);
+                    current = prev;
+                }
+                actions.reverse();
+                return Some(Recipe(actions));
+            }
+            queue.push_back(next);
+        }
+    }
+    None
+}
+
+/// A breadth-first search that expands each level on all cores.
+#[cfg(feature = "rayon")]
+mod parallel {
+    use super::{Progress, Visit};
+    use crate::{Action, LiquidData};
+    use rayon::prelude::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Explores everything that can be brewed from `start`, finding the same recipes as the
+    /// sequential search.
+    ///
+    /// Every step within a level gets a key from the position of its liquid in the frontier and
+    /// the action, which is the order the sequential search would take it in. When several steps
+    /// lead to the same new liquid, the one with the lowest key wins, and the next frontier is
+    /// sorted by key, so the result doesn't depend on how the work was split up.
+    pub(super) fn search_breadth_first(
+        start: LiquidData,
+        mut on_level: impl FnMut(Progress),
+    ) -> Vec<Option<Visit>> {
+        let mut visits: Vec<Option<Visit>> = vec![None; 0x8000];
+        let claims: Vec<AtomicU32> = (0..0x8000).map(|_| AtomicU32::new(u32::MAX)).collect();
+        let key = |position: usize, action: usize| (position * Action::ALL.len() + action) as u32;
+
+        visits[start.0 as usize] = Some(Visit::Root);
+        let mut frontier = vec![start];
+        let mut progress = Progress {
+            depth: 0,
+            discovered: 1,
+            frontier: 1,
+        };
+        while !frontier.is_empty() {
+            let steps = |(position, &ld): (usize, &LiquidData)| {
+                Action::ALL
+                    .iter()
+                    .enumerate()
+                    .map(move |(index, action)| (key(position, index), action.apply_to(ld)))
+            };
+            let is_new = |ld: &LiquidData| visits[ld.0 as usize].is_none();
+
+            let candidates: Vec<(u32, LiquidData)> = frontier
+                .par_iter()
+                .enumerate()
+                .flat_map_iter(steps)
+                .filter(|(_, next)| is_new(next))
+                .inspect(|&(key, next)| {
+                    claims[next.0 as usize].fetch_min(key, Ordering::Relaxed);
+                })
+                .collect();
+            let mut found: Vec<(u32, LiquidData)> = candidates
+                .into_par_iter()
+                .filter(|&(key, next)| claims[next.0 as usize].load(Ordering::Relaxed) == key)
+                .collect();
+            found.par_sort_unstable();
+
+            for &(key, next) in &found {
+                let position = key as usize / Action::ALL.len();
+                let action = Action::ALL[key as usize % Action::ALL.len()];
+                visits[next.0 as usize] = Some(Visit::Step(frontier[position], action));
+            }
+            frontier = found.into_iter().map(|(_, next)| next).collect();
+            progress.depth += 1;
+            progress.discovered += frontier.len();
+            progress.frontier = frontier.len();
+            on_level(progress);
+        }
+        visits
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use crate::{Action, LiquidData};
+
+        #[test]
+        fn parallel_search_matches_sequential() {
+            for start in [LiquidData::default(), LiquidData(16896)] {
+                let mut parallel_levels = Vec::new();
+                let mut sequential_levels = Vec::new();
+                assert_eq!(
+                    super::search_breadth_first(start, |p| parallel_levels.push(p)),
+                    super::super::search_breadth_first(
+                        start,
+                        &Action::ALL,
+                        Action::apply_to,
+                        |_| false,
+                        |p| sequential_levels.push(p)
+                    )
+                    .0
+                );
+                assert_eq!(parallel_levels, sequential_levels);
+            }
+        }
+    }
+}
+
+/// How many levels around the target `a_star` knows the exact distance for.
+const PERIMETER_DEPTH: u8 = 2;
+
+/// Finds a shortest recipe from water to `target` with A*.
+///
+/// Counting differing bits doesn't give a usable estimate here, since a single nether wart can
+/// set and clear many bits at once. Instead, the liquids within `PERIMETER_DEPTH` steps of the
+/// target are found with a backward search, and everything further away is estimated to be one
+/// step beyond that. This never overestimates, so the first recipe found is a shortest one.
+pub fn a_star(target: LiquidData) -> Option<Recipe> {
+    let start = LiquidData::default();
+    if target.0 as usize >= 0x8000 {
+        return None;
+    }
+
+    // estimate[dv] = exact distance to the target within the perimeter, one more outside
+    let mut estimate = vec![PERIMETER_DEPTH + 1; 0x8000];
+    estimate[target.0 as usize] = 0;
+    let mut frontier = vec![target];
+    for depth in 1..=PERIMETER_DEPTH {
+        let mut next_frontier = Vec::new();
+        for &ld in &frontier {
+            for &action in Action::ALL.iter() {
+                for prev in ld.preimages(action) {
+                    if estimate[prev.0 as usize] > depth {
+                        estimate[prev.0 as usize] = depth;
+                        next_frontier.push(prev);
+                    }
+                }
+            }
+        }
+        frontier = next_frontier;
+    }
+
+    let mut steps: Vec<Option<u32>> = vec![None; 0x8000];
+    let mut visits: Vec<Option<Visit>> = vec![None; 0x8000];
+    let mut heap = BinaryHeap::new();
+    steps[start.0 as usize] = Some(0);
+    visits[start.0 as usize] = Some(Visit::Root);
+    // Among equal estimates, continue with the liquid that is furthest along.
+    let total = |steps: u32, ld: LiquidData| steps + estimate[ld.0 as usize] as u32;
+    heap.push((Reverse(total(0, start)), 0, start.0));
+
+    while let Some((_, current_steps, dv)) = heap.pop() {
+        if dv == target.0 {
+            return Some(Recipe(trace(&visits, target)));
+        }
+        if steps[dv as usize] != Some(current_steps) {
+            // already reached in fewer steps
+            continue;
+        }
+        for &action in Action::ALL.iter() {
+            let next = action.apply_to(LiquidData(dv));
+            let next_steps = current_steps + 1;
+            if steps[next.0 as usize].is_none_or(|known| next_steps < known) {
+                steps[next.0 as usize] = Some(next_steps);
+                visits[next.0 as usize] = Some(Visit::Step(LiquidData(dv), action));
+                heap.push((Reverse(total(next_steps, next)), next_steps, next.0));
+            }
+        }
+    }
+    None
+}
+
+/// Finds up to `k` shortest distinct recipes from water to `target`, shortest first.
+///
+/// Only recipes that never brew the same liquid twice are considered, since any other recipe can
+/// be shortened by leaving out the detour. Recipes of equal length are returned in no particular
+/// order.
+///
+/// Each liquid is continued from at most `k` times, since a later partial recipe through it can
+/// only lead to longer recipes than the first `k`. That keeps the memory below `8 * k` partial
+/// recipes per liquid. Strictly, the first `k` can all be unable to continue without brewing a
+/// liquid twice, which would make this miss a recipe, but no such case is known.
+pub fn k_shortest(target: LiquidData, k: usize) -> Vec<Recipe> {
+    /// A partial recipe, stored as a step after the partial recipe at `parent`.
+    struct Node {
+        parent: usize,
+        action: Option<Action>,
+        ld: LiquidData,
+        steps: u32,
+    }
+
+    let start = LiquidData::default();
+    if k == 0 || target.0 as usize >= 0x8000 {
+        return Vec::new();
+    }
+
+    // remaining[dv] = number of steps still needed to get from dv to the target
+    let mut remaining: Vec<Option<u32>> = vec![None; 0x8000];
+    remaining[target.0 as usize] = Some(0);
+    let mut frontier = vec![target];
+    let mut depth = 0;
+    // Every liquid that can reach the target needs to be known, not just those closer than water.
+    while !frontier.is_empty() {
+        depth += 1;
+        let mut next_frontier = Vec::new();
+        for &ld in &frontier {
+            for &action in Action::ALL.iter() {
+                for prev in ld.preimages(action) {
+                    if remaining[prev.0 as usize].is_none() {
+                        remaining[prev.0 as usize] = Some(depth);
+                        next_frontier.push(prev);
+                    }
+                }
+            }
+        }
+        frontier = next_frontier;
+    }
+    let start_remaining = match remaining[start.0 as usize] {
+        Some(steps) => steps,
+        None => return Vec::new(),
+    };
+
+    // Expand partial recipes by how long they will be at least once finished. Since `remaining`
+    // never overestimates, finished recipes come out shortest first.
+    let mut nodes = vec![Node {
+        parent: 0,
+        action: None,
+        ld: start,
+        steps: 0,
+    }];
+    let mut heap = BinaryHeap::new();
+    heap.push(Reverse((start_remaining, 0usize)));
+    let mut found = Vec::new();
+    // expanded[dv] = how many partial recipes ending there were taken from the heap
+    let mut expanded = vec![0usize; 0x8000];
+    while let Some(Reverse((_, index))) = heap.pop() {
+        let node = &nodes[index];
+        if expanded[node.ld.0 as usize] == k {
+            continue;
+        }
+        expanded[node.ld.0 as usize] += 1;
+        if node.ld == target {
+            let mut actions = Vec::with_capacity(node.steps as usize);
+            let mut current = index;
+            while let Some(action) = nodes[current].action {
+                actions.push(action);
+                current = nodes[current].parent;
+            }
+            actions.reverse();
+            found.push(Recipe(actions));
+            if found.len() == k {
+                break;
+            }
+            continue;
+        }
+
+        let (ld, steps) = (node.ld, node.steps);
+        for &action in Action::ALL.iter() {
+            let next = action.apply_to(ld);
+            let next_remaining = match remaining[next.0 as usize] {
+                Some(steps) if expanded[next.0 as usize] < k => steps,
+                _ => continue,
+            };
+            // skip liquids that were already brewed earlier in this recipe
+            let mut current = index;
+            let revisits = loop {
+                if nodes[current].ld == next {
+                    break true;
+                }
+                if nodes[current].action.is_none() {
+                    break false;
+                }
+                current = nodes[current].parent;
+            };
+            if revisits {
+                continue;
+            }
+            nodes.push(Node {
+                parent: index,
+                action: Some(action),
+                ld: next,
+                steps: steps + 1,
+            });
+            heap.push(Reverse((steps + 1 + next_remaining, nodes.len() - 1)));
+        }
+    }
+    found
+}
+
+/// Builds the recipe through `meeting` from the visits of both searches.
+fn join(forward: &[Option<Visit>], backward: &[Option<Visit>], meeting: LiquidData) -> Recipe {
+    let mut actions = trace(forward, meeting);
+    let mut current = meeting;
+    while let Some(Visit::Step(next, action)) = backward[current.0 as usize] {
+        actions.push(action);
+        current = next;
+    }
+    Recipe(actions)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solver::{
+        a_star, bidirectional, breadth_first, breadth_first_from, constrained, k_shortest,
+        with_effects, ActionCosts, Constraints, Progress, RecipeTable, SearchStats,
+    };
+    use crate::{
+        effect_classes, Action, CustomIngredient, LiquidData, PotionIngredient, Recipe,
+        TransitionTable,
+    };
+
+    #[test]
+    fn table_recipes_produce_their_liquid() {
+        let table = RecipeTable::build();
+        assert_eq!(table.len(), 5339);
+        for (ld, recipe) in table.iter() {
+            assert_eq!(recipe.apply(LiquidData::default()), ld);
+        }
+        assert_eq!(table.recipe(LiquidData::default()).unwrap().0.len(), 0);
+    }
+
+    #[test]
+    fn progress_counts_every_level() {
+        let mut levels = Vec::new();
+        let table = RecipeTable::build_with_progress(LiquidData::default(), |p| levels.push(p));
+        let last = *levels.last().unwrap();
+        assert_eq!(last.discovered, table.len());
+        assert_eq!(last.frontier, 0);
+        // the last level finds nothing new
+        assert_eq!(last.depth, table.max_steps() + 1);
+        assert!(levels.iter().enumerate().all(|(i, p)| p.depth == i + 1));
+    }
+
+    #[test]
+    fn stats_add_up() {
+        let (table, stats) = RecipeTable::build_with_stats(LiquidData::default());
+        assert_eq!(stats.levels.len(), table.max_steps() + 1);
+        assert_eq!(stats.levels[0].expanded, 1);
+        let discovered: usize = stats.levels.iter().map(|level| level.discovered).sum();
+        assert_eq!(discovered + 1, table.len());
+        // every liquid but water is expanded exactly once, including those of the last level
+        let expanded: usize = stats.levels.iter().map(|level| level.expanded).sum();
+        assert_eq!(expanded, table.len());
+        assert!(stats.peak_queue() > 1);
+        let b = stats.branching_factor();
+        assert!(b > 1.0 && b < Action::ALL.len() as f64);
+        let found = b.powi(table.max_steps() as i32);
+        assert!((found - discovered as f64).abs() < 1e-6 * found);
+    }
+
+    #[test]
+    fn branching_factor_is_effective() {
+        // a search that finds 8 liquids in the first step and 56 in the second
+        let mut stats = SearchStats::start();
+        assert_eq!(stats.branching_factor(), 0.0);
+        for &(depth, discovered, frontier) in &[(1, 9, 8), (2, 65, 56), (3, 65, 0)] {
+            stats.record(Progress {
+                depth,
+                discovered,
+                frontier,
+            });
+        }
+        assert!((stats.branching_factor() - 8.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ties_go_to_the_smallest_recipe() {
+        let table = RecipeTable::build();
+        for (ld, recipe) in table.iter() {
+            // any other shortest recipe ends with a shortest recipe for one of the preimages
+            for &action in Action::ALL.iter() {
+                for prev in ld.preimages(action) {
+                    if let Some(mut other) = table.recipe(prev) {
+                        other.0.push(action);
+                        if other.0.len() == recipe.0.len() {
+                            assert!(recipe <= other, "{} should be {}", recipe, other);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn one_recipe_per_class() {
+        let table = RecipeTable::build();
+        let picked = table.cheapest_per_class(&ActionCosts::uniform());
+        // plain water is the only liquid without effects that needs no steps
+        assert_eq!(picked[0], (LiquidData(0), Recipe(vec![])));
+        let classes = effect_classes(table.iter().map(|(ld, _)| ld));
+        assert_eq!(picked.len(), classes.len());
+        for ((ld, recipe), class) in picked.iter().zip(&classes) {
+            assert!(class.liquids.contains(ld));
+            assert!(class
+                .liquids
+                .iter()
+                .all(|&other| table.recipe(other).unwrap().0.len() >= recipe.0.len()));
+        }
+    }
+
+    #[test]
+    fn fewer_actions_reach_less() {
+        let table = RecipeTable::build();
+        assert!(
+            RecipeTable::build_using(LiquidData::default(), &Action::ALL)
+                .iter()
+                .eq(table.iter())
+        );
+
+        let wart = Action::AddNetherWart;
+        let without_wart: Vec<Action> =
+            Action::ALL.iter().copied().filter(|&a| a != wart).collect();
+        let restricted = RecipeTable::build_using(LiquidData::default(), &without_wart);
+        assert!(restricted.len() < table.len());
+        let transitions = TransitionTable::new();
+        assert!(RecipeTable::build_with_transitions(
+            &transitions,
+            LiquidData::default(),
+            &without_wart
+        )
+        .iter()
+        .eq(restricted.iter()));
+        for (ld, recipe) in restricted.iter() {
+            assert!(!recipe.0.contains(&wart));
+            assert!(recipe.0.len() >= table.recipe(ld).unwrap().0.len());
+        }
+    }
+
+    #[test]
+    fn predecessors_end_recipes() {
+        let table = RecipeTable::build();
+        assert_eq!(table.predecessor(LiquidData::default()), None);
+        for (ld, recipe) in table.iter().skip(1) {
+            let (prev, action) = table.predecessor(ld).unwrap();
+            assert_eq!(recipe.0.last(), Some(&action));
+            assert_eq!(action.apply_to(prev), ld);
+        }
+        let unreachable = LiquidData::all()
+            .find(|ld| table.recipe(*ld).is_none())
+            .unwrap();
+        assert_eq!(table.predecessor(unreachable), None);
+    }
+
+    #[test]
+    fn uniform_costs_find_shortest_recipes() {
+        let shortest = RecipeTable::build();
+        let cheapest = RecipeTable::cheapest(&ActionCosts::uniform());
+        assert_eq!(cheapest.len(), shortest.len());
+        for (ld, recipe) in cheapest.iter() {
+            assert_eq!(recipe.apply(LiquidData::default()), ld);
+            assert_eq!(recipe.0.len(), shortest.recipe(ld).unwrap().0.len());
+        }
+    }
+
+    #[test]
+    fn expensive_actions_are_avoided() {
+        let blaze = Action::AddIngredient(PotionIngredient::BlazePowder);
+        let costs = ActionCosts::uniform().with(blaze, 100).unwrap();
+        let shortest = RecipeTable::build();
+        let cheapest = RecipeTable::cheapest(&costs);
+        for (ld, recipe) in cheapest.iter() {
+            assert_eq!(recipe.apply(LiquidData::default()), ld);
+            assert!(costs.recipe_cost(&recipe) <= costs.recipe_cost(&shortest.recipe(ld).unwrap()));
+        }
+        let blaze_uses = |table: &RecipeTable| {
+            table
+                .iter()
+                .map(|(_, recipe)| recipe.0.iter().filter(|&&a| a == blaze).count())
+                .sum::<usize>()
+        };
+        assert!(blaze_uses(&cheapest) < blaze_uses(&shortest));
+    }
+
+    #[test]
+    fn cheapest_using_matches_cheapest() {
+        let costs = ActionCosts::uniform()
+            .with(Action::AddIngredient(PotionIngredient::BlazePowder), 100)
+            .and_then(|costs| costs.with(Action::AddNetherWart, 3))
+            .unwrap();
+        let cheapest = RecipeTable::cheapest(&costs);
+        let using =
+            RecipeTable::cheapest_using(LiquidData::default(), &Action::ALL, |a| costs.cost(a));
+        assert!(cheapest.iter().eq(using.iter()));
+    }
+
+    #[test]
+    fn bidirectional_finds_shortest_recipes() {
+        let table = RecipeTable::build();
+        for (ld, recipe) in table.iter().step_by(41) {
+            let found = bidirectional(ld).expect("target is reachable");
+            assert_eq!(found.apply(LiquidData::default()), ld);
+            assert_eq!(found.0.len(), recipe.0.len(), "length for {}", ld.0);
+        }
+    }
+
+    #[test]
+    fn bidirectional_reports_unreachable_targets() {
+        let table = RecipeTable::build();
+        let unreachable = LiquidData::all()
+            .find(|ld| table.recipe(*ld).is_none())
+            .unwrap();
+        assert_eq!(bidirectional(unreachable), None);
+        assert_eq!(bidirectional(LiquidData(0x8000)), None);
+    }
+
+    #[test]
+    fn breadth_first_matches_table() {
+        let table = RecipeTable::build();
+        for (ld, recipe) in table.iter().step_by(97) {
+            assert_eq!(breadth_first(ld), Some(recipe));
+        }
+        assert_eq!(breadth_first(LiquidData(0x8000)), None);
+    }
+
+    #[test]
+    fn search_from_other_start() {
+        let start = LiquidData::default().apply_ingredient(PotionIngredient::FermentedSpiderEye);
+        let table = RecipeTable::build_from(start);
+        assert_eq!(table.recipe(start), Some(Recipe::default()));
+        for (ld, recipe) in table.iter().step_by(97) {
+            assert_eq!(recipe.apply(start), ld);
+            assert_eq!(breadth_first_from(start, ld), Some(recipe));
+        }
+        // water can't be brewed back once there is something in it
+        assert_eq!(breadth_first_from(start, LiquidData::default()), None);
+        assert_eq!(breadth_first_from(LiquidData(0x8000), start), None);
+    }
+
+    #[test]
+    fn effect_search_finds_shortest_match() {
+        use crate::PotionEffect::{Poison, Strength, Weakness};
+
+        let table = RecipeTable::build();
+        let (ld, recipe) = with_effects(&[Strength], &[]).unwrap();
+        assert_eq!(recipe.apply(LiquidData::default()), ld);
+        assert!(ld.effects().contains(&Strength));
+        let shortest = table
+            .iter()
+            .filter(|(ld, _)| ld.effects().contains(&Strength))
+            .map(|(_, r)| r.0.len())
+            .min();
+        assert_eq!(Some(recipe.0.len()), shortest);
+
+        let (ld, _) = with_effects(&[Poison], &[Weakness]).unwrap();
+        assert!(ld.effects().contains(&Poison));
+        assert!(!ld.effects().contains(&Weakness));
+        assert_eq!(with_effects(&[Strength], &[Strength]), None);
+    }
+
+    #[test]
+    fn k_shortest_are_distinct_and_ordered() {
+        let table = RecipeTable::build();
+        for (ld, recipe) in table.iter().step_by(331) {
+            let found = k_shortest(ld, 5);
+            assert!(!found.is_empty());
+            assert_eq!(found[0].0.len(), recipe.0.len(), "length for {}", ld.0);
+            for (i, r) in found.iter().enumerate() {
+                assert_eq!(r.apply(LiquidData::default()), ld);
+                assert!(found[..i].iter().all(|other| other != r));
+            }
+            assert!(found.windows(2).all(|w| w[0].0.len() <= w[1].0.len()));
+        }
+        assert_eq!(k_shortest(LiquidData(0x8000), 3), vec![]);
+        assert_eq!(
+            k_shortest(LiquidData::default(), 3),
+            vec![Recipe::default()]
+        );
+    }
+
+    #[test]
+    fn k_shortest_finds_every_short_recipe() {
+        // Everything two steps from water, by brute force over recipes that don't revisit a liquid.
+        let target = LiquidData::default()
+            .apply_ingredient(PotionIngredient::FermentedSpiderEye)
+            .apply_ingredient(PotionIngredient::Sugar);
+        let mut expected = Vec::new();
+        for &a in Action::ALL.iter() {
+            for &b in Action::ALL.iter() {
+                let first = a.apply_to(LiquidData::default());
+                if first != LiquidData::default() && first != target && b.apply_to(first) == target
+                {
+                    expected.push(Recipe(vec![a, b]));
+                }
+            }
+        }
+        let found = k_shortest(target, 100);
+        let short: Vec<_> = found.iter().filter(|r| r.0.len() == 2).collect();
+        assert_eq!(short.len(), expected.len());
+        assert!(expected.iter().all(|r| found.contains(r)));
+    }
+
+    #[test]
+    fn unconstrained_search_matches_table() {
+        let table = RecipeTable::build();
+        for (ld, recipe) in table.iter().step_by(211) {
+            let found = constrained(ld, &Constraints::default()).unwrap();
+            assert_eq!(found.0.len(), recipe.0.len(), "length for {}", ld.0);
+        }
+    }
+
+    #[test]
+    fn constraints_are_respected() {
+        let ghast_tear = Action::AddIngredient(PotionIngredient::GhastTear);
+        let constraints = Constraints::default()
+            .ban(ghast_tear)
+            .and_then(|c| c.ban(Action::Dilute))
+            .and_then(|c| c.limit(Action::AddNetherWart, 2))
+            .unwrap();
+        let table = RecipeTable::build();
+        let mut unreachable = 0;
+        for (ld, recipe) in table.iter().step_by(53) {
+            match constrained(ld, &constraints) {
+                Some(found) => {
+                    assert_eq!(found.apply(LiquidData::default()), ld);
+                    assert!(constraints.allows(&found));
+                    assert!(found.0.len() >= recipe.0.len());
+                }
+                None => unreachable += 1,
+            }
+        }
+        assert!(unreachable > 0);
+
+        // Healing needs a ghast tear
+        let healing = LiquidData::default().apply_ingredient(PotionIngredient::GhastTear);
+        assert_eq!(
+            constrained(healing, &Constraints::default().ban(ghast_tear).unwrap()),
+            None
+        );
+        assert!(!Constraints::default()
+            .limit(ghast_tear, 1)
+            .unwrap()
+            .allows(&"GG".parse().unwrap()));
+    }
+
+    #[test]
+    fn custom_ingredients_have_no_cost_or_limit() {
+        let pixie_dust = Action::AddIngredient(PotionIngredient::Custom(CustomIngredient::new(
+            "Pixie Dust",
+            'P',
+            &[2, 12],
+        )));
+        assert_eq!(ActionCosts::uniform().with(pixie_dust, 5), None);
+        assert_eq!(ActionCosts::uniform().cost(pixie_dust), 1);
+        assert_eq!(Constraints::default().ban(pixie_dust), None);
+        assert_eq!(Constraints::default().limit(pixie_dust, 2), None);
+        assert_eq!(Constraints::default().max_uses(pixie_dust), None);
+    }
+
+    #[test]
+    fn a_star_finds_shortest_recipes() {
+        let table = RecipeTable::build();
+        for (ld, recipe) in table.iter().step_by(41) {
+            let found = a_star(ld).expect("target is reachable");
+            assert_eq!(found.apply(LiquidData::default()), ld);
+            assert_eq!(found.0.len(), recipe.0.len(), "length for {}", ld.0);
+        }
+        assert_eq!(a_star(LiquidData::default()).unwrap().0.len(), 0);
+    }
+
+    #[test]
+    fn a_star_reports_unreachable_targets() {
+        let table = RecipeTable::build();
+        let unreachable = LiquidData::all()
+            .find(|ld| table.recipe(*ld).is_none())
+            .unwrap();
+        assert_eq!(a_star(unreachable), None);
+        assert_eq!(a_star(LiquidData(0x8000)), None);
+    }
+}
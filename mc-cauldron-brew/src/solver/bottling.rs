@@ -0,0 +1,237 @@
+//! Planning a single brew that fills a bottle of each of several potions.
+//!
+//! A cauldron holds three bottles, and the liquids along a recipe are often useful themselves,
+//! so one cauldron can give several different potions if bottles are taken at the right times.
+//! The planner searches breadth-first over the liquid, the fill level and which potions have
+//! been bottled, so the plan takes as few clicks as possible, counting every bottle.
+
+use crate::{graph, Action, CauldronState, LiquidData, PotionIngredient, Step, TransitionTable};
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+
+/// The most potions one plan can bottle, which keeps the search below 8.4 million states.
+pub const MAX_TARGETS: usize = 6;
+
+/// The clicks that brew and bottle several potions, see `plan_bottling`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct BottlingPlan {
+    /// The clicks, starting with an empty cauldron.
+    pub steps: Vec<Step>,
+    /// Each potion and the index of the `Step::UseGlassBottle` in `steps` that fills it, in the
+    /// order they are bottled.
+    pub bottles: Vec<(LiquidData, usize)>,
+}
+
+/// The reason no plan can be made.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum PlanError {
+    /// More than `MAX_TARGETS` different potions were asked for.
+    TooManyTargets,
+    /// This potion can't be brewed from water.
+    Unreachable(LiquidData),
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanError::TooManyTargets => {
+                write!(f, "can't plan for more than {} potions", MAX_TARGETS)
+            }
+            PlanError::Unreachable(ld) => write!(f, "{} can't be brewed from water", ld.0),
+        }
+    }
+}
+
+impl Error for PlanError {}
+
+/// A cauldron and which targets have been bottled, packed as `bottled << 17 | level << 15 |
+/// liquid`. The liquid of an empty cauldron is always 0, so that it is one state.
+#[derive(Copy, Clone, Eq, PartialEq)]
+struct Node(u32);
+
+impl Node {
+    fn new(cauldron: CauldronState, bottled: u32) -> Self {
+        let liquid = if cauldron.is_empty() {
+            0
+        } else {
+            cauldron.liquid.0 as u32
+        };
+        Node(bottled << 17 | (cauldron.level as u32) << 15 | liquid)
+    }
+
+    fn cauldron(self) -> CauldronState {
+        CauldronState {
+            liquid: LiquidData((self.0 & 0x7fff) as u16),
+            level: ((self.0 >> 15) & 3) as u8,
+        }
+    }
+
+    fn bottled(self) -> u32 {
+        self.0 >> 17
+    }
+}
+
+/// The clicks that can be made on a cauldron, in the order the search tries them.
+const STEPS: [Step; 9] = [
+    Step::UseGlassBottle,
+    Step::UseIngredient(PotionIngredient::Sugar),
+    Step::UseIngredient(PotionIngredient::GhastTear),
+    Step::UseIngredient(PotionIngredient::SpiderEye),
+    Step::UseIngredient(PotionIngredient::FermentedSpiderEye),
+    Step::UseIngredient(PotionIngredient::BlazePowder),
+    Step::UseIngredient(PotionIngredient::MagmaCream),
+    Step::UseWaterBucket,
+    Step::UseNetherWart,
+];
+
+/// Takes a step, if it is possible, and returns the new node.
+fn click(node: Node, step: Step, targets: &[LiquidData], table: &TransitionTable) -> Option<Node> {
+    let mut cauldron = node.cauldron();
+    let mut bottled = node.bottled();
+    let action = match step {
+        Step::UseGlassBottle => {
+            let potion = cauldron.take_bottle().ok()?;
+            if let Some(i) = targets.iter().position(|&t| t == potion) {
+                bottled |= 1 << i;
+            }
+            return Some(Node::new(cauldron, bottled));
+        }
+        Step::UseWaterBucket => {
+            cauldron.add_water_bucket().ok()?;
+            return Some(Node::new(cauldron, bottled));
+        }
+        Step::UseIngredient(ingredient) => Action::AddIngredient(ingredient),
+        Step::UseNetherWart => Action::AddNetherWart,
+    };
+    if cauldron.is_empty() {
+        return None;
+    }
+    cauldron.liquid = table.apply(action, cauldron.liquid);
+    Some(Node::new(cauldron, bottled))
+}
+
+/// Finds the fewest clicks that fill a bottle of every potion in `targets`, starting with an
+/// empty cauldron. Potions that are listed twice are only bottled once.
+pub fn plan_bottling(targets: &[LiquidData]) -> Result<BottlingPlan, PlanError> {
+    let mut unique: Vec<LiquidData> = Vec::new();
+    for &target in targets {
+        if !unique.contains(&target) {
+            unique.push(target);
+        }
+    }
+    if unique.len() > MAX_TARGETS {
+        return Err(PlanError::TooManyTargets);
+    }
+    let reachable = graph::reachable(LiquidData::default());
+    if let Some(&missing) = unique.iter().find(|t| reachable.binary_search(t).is_err()) {
+        return Err(PlanError::Unreachable(missing));
+    }
+
+    let table = TransitionTable::new();
+    let done = (1u32 << unique.len()) - 1;
+    let states = ((done as usize) + 1) << 17;
+    // For every node that was reached: the node before it and the index of the step in `STEPS`.
+    let mut parents: Vec<Option<(Node, u8)>> = vec![None; states];
+    let start = Node::new(CauldronState::empty(), 0);
+    let mut queue = VecDeque::new();
+    queue.push_back(start);
+    let mut end = start;
+    while let Some(node) = queue.pop_front() {
+        if node.bottled() == done {
+            end = node;
+            break;
+        }
+        for (i, &step) in STEPS.iter().enumerate() {
+            if let Some(next) = click(node, step, &unique, &table) {
+                if next != start && parents[next.0 as usize].is_none() {
+                    parents[next.0 as usize] = Some((node, i as u8));
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    let mut path = Vec::new();
+    let mut node = end;
+    while let Some((prev, i)) = parents[node.0 as usize] {
+        path.push((prev, STEPS[i as usize], node));
+        node = prev;
+    }
+    path.reverse();
+    let mut plan = BottlingPlan {
+        steps: Vec::with_capacity(path.len()),
+        bottles: Vec::new(),
+    };
+    for (prev, step, next) in path {
+        let newly = next.bottled() & !prev.bottled();
+        if newly != 0 {
+            let potion = unique[newly.trailing_zeros() as usize];
+            plan.bottles.push((potion, plan.steps.len()));
+        }
+        plan.steps.push(step);
+    }
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{plan_bottling, PlanError, MAX_TARGETS};
+    use crate::{CauldronState, LiquidData, PotionIngredient, Step};
+
+    #[test]
+    fn potions_along_the_way_are_bottled() {
+        // E gives 1184 and EN gives 1088, so both come from one cauldron
+        let plan = plan_bottling(&[LiquidData(1088), LiquidData(1184)]).unwrap();
+        assert_eq!(
+            plan.steps,
+            vec![
+                Step::UseWaterBucket,
+                Step::UseIngredient(PotionIngredient::SpiderEye),
+                Step::UseGlassBottle,
+                Step::UseNetherWart,
+                Step::UseGlassBottle,
+            ]
+        );
+        assert_eq!(
+            plan.bottles,
+            vec![(LiquidData(1184), 2), (LiquidData(1088), 4)]
+        );
+    }
+
+    #[test]
+    fn plans_follow_the_cauldron_rules() {
+        let targets = [LiquidData(16896), LiquidData(16384), LiquidData(25486)];
+        let plan = plan_bottling(&targets).unwrap();
+        let mut cauldron = CauldronState::empty();
+        let mut bottled = Vec::new();
+        for (i, &step) in plan.steps.iter().enumerate() {
+            match step {
+                Step::UseGlassBottle => {
+                    let potion = cauldron.take_bottle().unwrap();
+                    if plan.bottles.contains(&(potion, i)) {
+                        bottled.push(potion);
+                    }
+                }
+                Step::UseWaterBucket => cauldron.add_water_bucket().unwrap(),
+                Step::UseIngredient(ingredient) => cauldron.add_ingredient(ingredient).unwrap(),
+                Step::UseNetherWart => cauldron.add_wart().unwrap(),
+            }
+        }
+        bottled.sort();
+        assert_eq!(
+            bottled,
+            [LiquidData(16384), LiquidData(16896), LiquidData(25486)]
+        );
+    }
+
+    #[test]
+    fn impossible_plans_are_rejected() {
+        let many: Vec<_> = (0..=MAX_TARGETS as u16).map(LiquidData).collect();
+        assert_eq!(plan_bottling(&many), Err(PlanError::TooManyTargets));
+        assert_eq!(
+            plan_bottling(&[LiquidData(1184), LiquidData(2)]),
+            Err(PlanError::Unreachable(LiquidData(2)))
+        );
+    }
+}
@@ -0,0 +1,324 @@
+//! Saving tables and searches to disk and loading them again.
+//!
+//! Both formats start with a four byte tag and a version, followed by little-endian integers.
+//! Every visit is stored as one u32: the previous dv shifted left by 3 with the index of the
+//! action in the low bits, `u32::MAX` for liquids that weren't reached and `u32::MAX - 1` for the
+//! start.
+
+use super::{ActionCosts, CheapestSearch, RecipeTable, Visit};
+use crate::{Action, LiquidData};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::{self, Read, Write};
+
+const TABLE_TAG: &[u8; 4] = b"MCRT";
+const SEARCH_TAG: &[u8; 4] = b"MCCS";
+const VERSION: u8 = 1;
+
+const UNREACHED: u32 = u32::MAX;
+const ROOT: u32 = u32::MAX - 1;
+
+impl RecipeTable {
+    /// Writes the table in a compact binary format, to be read back by `RecipeTable::read_from`.
+    ///
+    /// Fails with `io::ErrorKind::InvalidInput` if a step adds a custom ingredient, which has no
+    /// index to store.
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        write_header(&mut writer, TABLE_TAG)?;
+        write_visits(&mut writer, &self.visits)
+    }
+
+    /// Reads a table written by `RecipeTable::write_to`.
+    ///
+    /// Fails with `io::ErrorKind::InvalidData` if the data isn't a table or a step in it doesn't
+    /// lead where it claims to.
+    pub fn read_from(mut reader: impl Read) -> io::Result<Self> {
+        read_header(&mut reader, TABLE_TAG)?;
+        let visits = read_visits(&mut reader)?;
+        check_visits(&visits)?;
+        Ok(RecipeTable { visits })
+    }
+}
+
+impl CheapestSearch {
+    /// Writes the state of the search, so that it can be continued after
+    /// `CheapestSearch::read_from`.
+    ///
+    /// Fails with `io::ErrorKind::InvalidInput` if the search doesn't take every action in
+    /// `Action::ALL`, since the file doesn't store which ones it takes.
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        if self.actions != Action::ALL {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "only searches with every built-in action can be saved",
+            ));
+        }
+        write_header(&mut writer, SEARCH_TAG)?;
+        for &cost in self.costs.0.iter() {
+            writer.write_all(&cost.to_le_bytes())?;
+        }
+        write_visits(&mut writer, &self.visits)?;
+        for best in &self.best {
+            let (cost, steps) = best.unwrap_or((u64::MAX, u32::MAX));
+            writer.write_all(&cost.to_le_bytes())?;
+            writer.write_all(&steps.to_le_bytes())?;
+        }
+        writer.write_all(&(self.heap.len() as u32).to_le_bytes())?;
+        for Reverse((cost, steps, dv)) in self.heap.iter() {
+            writer.write_all(&cost.to_le_bytes())?;
+            writer.write_all(&steps.to_le_bytes())?;
+            writer.write_all(&dv.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Reads a search written by `CheapestSearch::write_to`.
+    ///
+    /// Fails with `io::ErrorKind::InvalidData` if the data isn't a search, or its steps, costs and
+    /// queue don't fit together.
+    pub fn read_from(mut reader: impl Read) -> io::Result<Self> {
+        read_header(&mut reader, SEARCH_TAG)?;
+        let mut costs = [0; 8];
+        for cost in costs.iter_mut() {
+            *cost = read_u32(&mut reader)?;
+        }
+        let visits = read_visits(&mut reader)?;
+        check_visits(&visits)?;
+        // The cheapest recipes never brew a liquid twice, so they have fewer steps than there are
+        // liquids, which also keeps their cost far from overflowing.
+        let max_cost = costs.iter().copied().max().unwrap_or(0) as u64;
+        let mut best = Vec::with_capacity(0x8000);
+        for visit in &visits {
+            let cost = read_u64(&mut reader)?;
+            let steps = read_u32(&mut reader)?;
+            if cost == u64::MAX {
+                best.push(None);
+                continue;
+            }
+            if visit.is_none() {
+                return Err(invalid_data("cost for a liquid that wasn't reached"));
+            }
+            if steps >= 0x8000 || cost > steps as u64 * max_cost {
+                return Err(invalid_data("cost or steps out of range"));
+            }
+            best.push(Some((cost, steps)));
+        }
+        let len = read_u32(&mut reader)? as usize;
+        let mut heap = BinaryHeap::with_capacity(len.min(0x8000 * Action::ALL.len()));
+        for _ in 0..len {
+            let cost = read_u64(&mut reader)?;
+            let steps = read_u32(&mut reader)?;
+            let dv = read_u16(&mut reader)?;
+            if dv >= 0x8000 {
+                return Err(invalid_data("queued liquid out of range"));
+            }
+            // entries that were overtaken by a cheaper recipe stay queued, but none are cheaper
+            if best[dv as usize].is_none_or(|known| (cost, steps) < known) {
+                return Err(invalid_data("queued recipe doesn't match the best one"));
+            }
+            heap.push(Reverse((cost, steps, dv)));
+        }
+        Ok(CheapestSearch {
+            costs: ActionCosts(costs),
+            actions: Action::ALL.to_vec(),
+            best,
+            visits,
+            heap,
+        })
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn write_header(writer: &mut impl Write, tag: &[u8; 4]) -> io::Result<()> {
+    writer.write_all(tag)?;
+    writer.write_all(&[VERSION])
+}
+
+fn read_header(reader: &mut impl Read, tag: &[u8; 4]) -> io::Result<()> {
+    let mut header = [0; 5];
+    reader.read_exact(&mut header)?;
+    if &header[..4] != tag {
+        return Err(invalid_data("unexpected file type"));
+    }
+    if header[4] != VERSION {
+        return Err(invalid_data("unsupported version"));
+    }
+    Ok(())
+}
+
+fn write_visits(writer: &mut impl Write, visits: &[Option<Visit>]) -> io::Result<()> {
+    for visit in visits {
+        let encoded = match visit {
+            None => UNREACHED,
+            Some(Visit::Root) => ROOT,
+            Some(Visit::Step(_, action)) if action.index() >= Action::ALL.len() => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("a step uses custom ingredient {}", action),
+                ));
+            }
+            Some(Visit::Step(prev, action)) => (prev.0 as u32) << 3 | action.index() as u32,
+        };
+        writer.write_all(&encoded.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+fn read_visits(reader: &mut impl Read) -> io::Result<Vec<Option<Visit>>> {
+    (0..0x8000)
+        .map(|_| match read_u32(reader)? {
+            UNREACHED => Ok(None),
+            ROOT => Ok(Some(Visit::Root)),
+            encoded if encoded >> 3 < 0x8000 => Ok(Some(Visit::Step(
+                LiquidData((encoded >> 3) as u16),
+                Action::ALL[(encoded & 7) as usize],
+            ))),
+            _ => Err(invalid_data("previous liquid out of range")),
+        })
+        .collect()
+}
+
+/// Makes sure that every step leads where it claims to and every recipe ends at the start, so
+/// that following the steps can't loop.
+fn check_visits(visits: &[Option<Visit>]) -> io::Result<()> {
+    for (dv, visit) in visits.iter().enumerate() {
+        if let Some(Visit::Step(prev, action)) = *visit {
+            if action.apply_to(prev).0 as usize != dv {
+                return Err(invalid_data("step doesn't lead to its liquid"));
+            }
+        }
+        let mut current = dv;
+        for _ in 0..=visits.len() {
+            match visits[current] {
+                None if current == dv => break,
+                None => return Err(invalid_data("step from a liquid that wasn't reached")),
+                Some(Visit::Root) => break,
+                Some(Visit::Step(prev, _)) => current = prev.0 as usize,
+            }
+        }
+        if !matches!(visits[current], None | Some(Visit::Root)) {
+            return Err(invalid_data("steps form a loop"));
+        }
+    }
+    Ok(())
+}
+
+fn read_u16(reader: &mut impl Read) -> io::Result<u16> {
+    let mut bytes = [0; 2];
+    reader.read_exact(&mut bytes)?;
+    Ok(u16::from_le_bytes(bytes))
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solver::{ActionCosts, CheapestSearch, RecipeTable};
+    use crate::{Action, CustomIngredient, IngredientRegistry, LiquidData, PotionIngredient};
+    use std::io;
+
+    #[test]
+    fn tables_round_trip() {
+        let table = RecipeTable::build();
+        let mut bytes = Vec::new();
+        table.write_to(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 5 + 4 * 0x8000);
+        let loaded = RecipeTable::read_from(&bytes[..]).unwrap();
+        assert!(table.iter().eq(loaded.iter()));
+    }
+
+    #[test]
+    fn interrupted_search_gives_the_same_table() {
+        let costs = ActionCosts::uniform()
+            .with(Action::AddIngredient(PotionIngredient::BlazePowder), 5)
+            .unwrap();
+        let mut search = CheapestSearch::new(&costs);
+        assert!(!search.run(1000));
+        let mut bytes = Vec::new();
+        search.write_to(&mut bytes).unwrap();
+
+        let mut resumed = CheapestSearch::read_from(&bytes[..]).unwrap();
+        assert_eq!(resumed.costs(), &costs);
+        assert!(resumed.run(usize::MAX));
+        let expected = RecipeTable::cheapest(&costs);
+        assert!(resumed.into_table().iter().eq(expected.iter()));
+
+        let partial = CheapestSearch::using(LiquidData::default(), &[Action::Dilute], costs);
+        let err = partial.write_to(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn bad_data_is_rejected() {
+        let mut bytes = Vec::new();
+        RecipeTable::build().write_to(&mut bytes).unwrap();
+
+        let mut wrong_tag = bytes.clone();
+        wrong_tag[0] = b'X';
+        let err = RecipeTable::read_from(&wrong_tag[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // make water (dv 0) claim to be brewed from dv 1 by adding sugar
+        let mut wrong_step = bytes.clone();
+        wrong_step[5..9].copy_from_slice(&(1u32 << 3).to_le_bytes());
+        let err = RecipeTable::read_from(&wrong_step[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let err = RecipeTable::read_from(&bytes[..100]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn inconsistent_searches_are_rejected() {
+        let mut bytes = Vec::new();
+        CheapestSearch::new(&ActionCosts::uniform())
+            .write_to(&mut bytes)
+            .unwrap();
+        let best = 5 + 4 * 8 + 4 * 0x8000;
+        let queue = best + 12 * 0x8000 + 4;
+        assert!(CheapestSearch::read_from(&bytes[..]).is_ok());
+
+        // water claims a recipe so long that taking another step would overflow
+        let mut overflowing = bytes.clone();
+        overflowing[best + 8..best + 12].copy_from_slice(&u32::MAX.to_le_bytes());
+        overflowing[queue + 8..queue + 12].copy_from_slice(&u32::MAX.to_le_bytes());
+        let err = CheapestSearch::read_from(&overflowing[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // the queue holds a liquid that has no recipe
+        let mut unknown = bytes.clone();
+        unknown[queue + 12..queue + 14].copy_from_slice(&5u16.to_le_bytes());
+        let err = CheapestSearch::read_from(&unknown[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // the queue holds a recipe for water that is cheaper than the best one
+        let mut cheaper = bytes.clone();
+        cheaper[best + 8..best + 12].copy_from_slice(&1u32.to_le_bytes());
+        let err = CheapestSearch::read_from(&cheaper[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn custom_ingredients_are_rejected() {
+        let mut registry = IngredientRegistry::new();
+        registry
+            .register(CustomIngredient::new("Pixie Dust", 'P', &[2, 12]))
+            .unwrap();
+        let table = registry.recipe_table(LiquidData::default());
+        let err = table.write_to(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,117 @@
+//! Enumerating every liquid that a few steps can brew, for exhaustive tests and statistics on
+//! short recipes.
+
+use crate::{Action, LiquidData, Recipe};
+use std::collections::VecDeque;
+
+/// Iterates over every liquid that can be brewed from `start` in at most `max_len` steps of
+/// `actions`, each with one recipe for it, see `recipes_up_to`.
+#[derive(Clone, Debug)]
+pub struct RecipesUpTo {
+    /// The actions, in recipe order.
+    actions: Vec<Action>,
+    max_len: usize,
+    seen: Vec<bool>,
+    queue: VecDeque<(LiquidData, Recipe)>,
+}
+
+/// Enumerates the liquids that can be brewed from `start` in at most `max_len` steps, with the
+/// shortest recipe for each, starting with `start` itself and its empty recipe.
+///
+/// Recipes that brew a liquid that was already found are left out, so every liquid comes up once.
+/// The liquids come in the order of their recipes: shorter ones first, and those of equal length
+/// ordered like their letters, so the recipes are the same as those of `RecipeTable::build_using`.
+/// Liquids are found one step at a time, so only those of the last two lengths are kept.
+///
+/// # Panics
+///
+/// If `start` has any bit above the 15 the game uses set.
+pub fn recipes_up_to(start: LiquidData, actions: &[Action], max_len: usize) -> RecipesUpTo {
+    assert!(start.0 < 0x8000, "{} is not a valid liquid", start.0);
+    let mut actions = actions.to_vec();
+    actions.sort();
+    actions.dedup();
+    let mut seen = vec![false; 0x8000];
+    seen[start.0 as usize] = true;
+    RecipesUpTo {
+        actions,
+        max_len,
+        seen,
+        queue: VecDeque::from(vec![(start, Recipe::default())]),
+    }
+}
+
+impl Iterator for RecipesUpTo {
+    type Item = (LiquidData, Recipe);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (ld, recipe) = self.queue.pop_front()?;
+        if recipe.0.len() < self.max_len {
+            for &action in &self.actions {
+                let next = action.apply_to(ld);
+                if !self.seen[next.0 as usize] {
+                    self.seen[next.0 as usize] = true;
+                    let mut longer = recipe.clone();
+                    longer.0.push(action);
+                    self.queue.push_back((next, longer));
+                }
+            }
+        }
+        Some((ld, recipe))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::recipes_up_to;
+    use crate::fixtures::{SPIDER_EYE_WART, STRENGTH, WATER};
+    use crate::solver::RecipeTable;
+    use crate::{Action, LiquidData, Recipe};
+
+    #[test]
+    fn short_recipes_match_the_table() {
+        let table = RecipeTable::build();
+        for max_len in 0..=4 {
+            let found: Vec<(LiquidData, Recipe)> =
+                recipes_up_to(WATER.liquid, &Action::ALL, max_len).collect();
+            let expected: Vec<_> = table
+                .iter()
+                .filter(|(_, recipe)| recipe.0.len() <= max_len)
+                .collect();
+            assert_eq!(found.len(), expected.len(), "{}", max_len);
+            for (ld, recipe) in &found {
+                assert_eq!(table.recipe(*ld).as_ref(), Some(recipe));
+            }
+            assert!(found
+                .windows(2)
+                .all(|w| (w[0].1 .0.len(), &w[0].1) < (w[1].1 .0.len(), &w[1].1)));
+        }
+    }
+
+    #[test]
+    fn every_liquid_comes_up_once() {
+        let all: Vec<_> = recipes_up_to(WATER.liquid, &Action::ALL, usize::MAX).collect();
+        assert_eq!(all.len(), RecipeTable::build().len());
+        assert_eq!(all[0], (WATER.liquid, Recipe::default()));
+        let mut liquids: Vec<_> = all.iter().map(|(ld, _)| *ld).collect();
+        liquids.sort();
+        liquids.dedup();
+        assert_eq!(liquids.len(), all.len());
+
+        let strength = STRENGTH.recipe.unwrap().len();
+        assert!(recipes_up_to(WATER.liquid, &Action::ALL, strength)
+            .any(|(ld, _)| ld == STRENGTH.liquid));
+        assert!(!recipes_up_to(WATER.liquid, &Action::ALL, strength - 1)
+            .any(|(ld, _)| ld == STRENGTH.liquid));
+    }
+
+    #[test]
+    fn only_the_given_actions_are_taken() {
+        let actions = [Action::AddNetherWart, Action::from_letter('E').unwrap()];
+        let found: Vec<_> = recipes_up_to(WATER.liquid, &actions, 2).collect();
+        assert!(found.contains(&(SPIDER_EYE_WART.liquid, "EN".parse().unwrap())));
+        assert!(found
+            .iter()
+            .all(|(_, recipe)| recipe.0.iter().all(|a| actions.contains(a))));
+    }
+}
@@ -0,0 +1,137 @@
+//! A fixed-layout binary table with the recipe for every dv, for programs that want to embed the
+//! results instead of searching or parsing text.
+//!
+//! The table starts with an 8 byte header: the tag `MCLT`, the format version (1), the record size
+//! (16) and two zero bytes. It is followed by one 16 byte record for each dv from 0 to 32767:
+//!
+//! | bytes | content                                                           |
+//! |-------|-------------------------------------------------------------------|
+//! | 0     | number of steps, or 255 if the liquid can't be brewed             |
+//! | 1..4  | the potion color as red, green, blue                              |
+//! | 4..16 | the actions as a little-endian number, 3 bits each from the lowest |
+//!
+//! Actions are numbered in recipe letter order, `SGEFBCWN`, from 0 to 7.
+//!
+//! So the record for dv `n` starts at byte `8 + 16 * n`, and the whole table is 512 KiB.
+
+use super::RecipeTable;
+use crate::{Action, LiquidData, Recipe};
+use std::io::{self, Write};
+
+const TAG: &[u8; 4] = b"MCLT";
+const VERSION: u8 = 1;
+const HEADER_SIZE: usize = 8;
+const RECORD_SIZE: usize = 16;
+const UNREACHABLE: u8 = 255;
+
+/// The longest recipe a record has room for.
+pub const MAX_STEPS: usize = 32;
+
+impl RecipeTable {
+    /// Writes the lookup table described in the module documentation.
+    ///
+    /// Fails with `io::ErrorKind::InvalidInput` if a recipe is longer than `MAX_STEPS`, which
+    /// doesn't happen for the shortest recipes, or adds a custom ingredient, which has no number.
+    pub fn write_lookup(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(TAG)?;
+        writer.write_all(&[VERSION, RECORD_SIZE as u8, 0, 0])?;
+        for ld in LiquidData::all() {
+            let mut record = [0; RECORD_SIZE];
+            match self.recipe(ld) {
+                None => record[0] = UNREACHABLE,
+                Some(recipe) => {
+                    if recipe.0.len() > MAX_STEPS {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!("the recipe for {} is longer than {} steps", ld.0, MAX_STEPS),
+                        ));
+                    }
+                    if let Some(custom) = recipe.0.iter().find(|a| a.index() >= Action::ALL.len()) {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!("the recipe for {} uses custom ingredient {}", ld.0, custom),
+                        ));
+                    }
+                    record[0] = recipe.0.len() as u8;
+                    record[1..4].copy_from_slice(&ld.color().to_be_bytes()[1..]);
+                    let actions = recipe
+                        .0
+                        .iter()
+                        .enumerate()
+                        .fold(0u128, |bits, (i, action)| {
+                            bits | (action.index() as u128) << (3 * i)
+                        });
+                    record[4..].copy_from_slice(&actions.to_le_bytes()[..12]);
+                }
+            }
+            writer.write_all(&record)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether `table` looks like it was written by `RecipeTable::write_lookup`: it has the right
+/// header and size.
+pub fn is_lookup_table(table: &[u8]) -> bool {
+    table.len() == HEADER_SIZE + RECORD_SIZE * 0x8000
+        && table[..4] == TAG[..]
+        && table[4] == VERSION
+        && table[5] == RECORD_SIZE as u8
+}
+
+/// Looks up the recipe for `target` in a table written by `RecipeTable::write_lookup`, e.g. one
+/// embedded with `include_bytes!`.
+///
+/// Returns `None` if the liquid can't be brewed or the table is too short or not a lookup table.
+pub fn lookup(table: &[u8], target: LiquidData) -> Option<Recipe> {
+    if !is_lookup_table(table) {
+        return None;
+    }
+    let start = HEADER_SIZE + RECORD_SIZE * target.0 as usize;
+    let record = table.get(start..start + RECORD_SIZE)?;
+    let steps = record[0] as usize;
+    if steps > MAX_STEPS {
+        return None;
+    }
+    let mut bytes = [0; 16];
+    bytes[..12].copy_from_slice(&record[4..]);
+    let actions = u128::from_le_bytes(bytes);
+    Some(Recipe(
+        (0..steps)
+            .map(|i| Action::ALL[(actions >> (3 * i)) as usize & 7])
+            .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_lookup_table, lookup};
+    use crate::solver::RecipeTable;
+    use crate::LiquidData;
+
+    #[test]
+    fn lookup_matches_table() {
+        let table = RecipeTable::build();
+        let mut bytes = Vec::new();
+        table.write_lookup(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 8 + 16 * 0x8000);
+        for ld in LiquidData::all() {
+            assert_eq!(lookup(&bytes, ld), table.recipe(ld));
+        }
+        assert_eq!(lookup(&bytes, LiquidData(0x8000)), None);
+        assert_eq!(lookup(&bytes[..100], LiquidData(100)), None);
+        assert!(is_lookup_table(&bytes));
+        assert!(!is_lookup_table(&bytes[..bytes.len() - 1]));
+    }
+
+    #[test]
+    fn records_contain_the_color() {
+        let table = RecipeTable::build();
+        let mut bytes = Vec::new();
+        table.write_lookup(&mut bytes).unwrap();
+        let ld = LiquidData(16896);
+        let record = &bytes[8 + 16 * ld.0 as usize..][..16];
+        assert_eq!(record[0], 1);
+        assert_eq!(record[1..4], ld.color().to_be_bytes()[1..]);
+    }
+}
@@ -0,0 +1,225 @@
+//! Recipes spelled out as the clicks a player makes, see `Recipe::expand`.
+
+use crate::{Action, CauldronState, CustomIngredient, PotionIngredient, Recipe, MAX_LEVEL};
+use std::fmt;
+
+/// One click on a cauldron, with the item that is held.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Step {
+    /// Fills an empty cauldron with water, or tops it up and dilutes what is in it.
+    UseWaterBucket,
+    /// Fills a bottle from the cauldron, which makes room for a water bucket.
+    UseGlassBottle,
+    UseIngredient(PotionIngredient),
+    UseNetherWart,
+}
+
+impl PotionIngredient {
+    /// The name of the item, e.g. "Fermented Spider Eye".
+    pub fn item_name(self) -> &'static str {
+        match self {
+            PotionIngredient::Sugar => "Sugar",
+            PotionIngredient::GhastTear => "Ghast Tear",
+            PotionIngredient::SpiderEye => "Spider Eye",
+            PotionIngredient::FermentedSpiderEye => "Fermented Spider Eye",
+            PotionIngredient::BlazePowder => "Blaze Powder",
+            PotionIngredient::MagmaCream => "Magma Cream",
+            PotionIngredient::Custom(ingredient) => ingredient.name(),
+        }
+    }
+}
+
+impl Step {
+    /// The name of the item to use on the cauldron.
+    pub fn item_name(self) -> &'static str {
+        match self {
+            Step::UseWaterBucket => "Water Bucket",
+            Step::UseGlassBottle => "Glass Bottle",
+            Step::UseIngredient(ingredient) => ingredient.item_name(),
+            Step::UseNetherWart => "Nether Wart",
+        }
+    }
+}
+
+impl fmt::Display for Step {
+    /// Formats the step as an instruction, e.g. "Use a Glass Bottle on the cauldron".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let article = match self {
+            Step::UseWaterBucket | Step::UseGlassBottle => "a ",
+            _ => "",
+        };
+        write!(f, "Use {}{} on the cauldron", article, self.item_name())
+    }
+}
+
+/// The items a recipe uses up, see `Recipe::materials`.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct Materials {
+    /// Including the one that fills the cauldron at the start.
+    pub water_buckets: u32,
+    /// Bottles filled to make room for water, which end up holding a potion nobody asked for.
+    pub glass_bottles: u32,
+    /// How many of each ingredient, in the order of `PotionIngredient::ALL`.
+    pub ingredients: [u32; 6],
+    pub nether_wart: u32,
+    /// How many of each custom ingredient, in the order they are first used.
+    pub custom: Vec<(CustomIngredient, u32)>,
+}
+
+impl Materials {
+    /// How many of `ingredient` are used.
+    pub fn ingredient(&self, ingredient: PotionIngredient) -> u32 {
+        match ingredient {
+            PotionIngredient::Custom(custom) => self
+                .custom
+                .iter()
+                .find(|&&(c, _)| c == custom)
+                .map_or(0, |&(_, count)| count),
+            _ => self.ingredients[Action::AddIngredient(ingredient).index()],
+        }
+    }
+
+    /// The number of items used, except water buckets, which can be refilled.
+    pub fn consumed(&self) -> u32 {
+        self.glass_bottles
+            + self.ingredients.iter().sum::<u32>()
+            + self.nether_wart
+            + self.custom.iter().map(|&(_, count)| count).sum::<u32>()
+    }
+
+    fn add(&mut self, step: Step) {
+        match step {
+            Step::UseWaterBucket => self.water_buckets += 1,
+            Step::UseGlassBottle => self.glass_bottles += 1,
+            Step::UseIngredient(PotionIngredient::Custom(custom)) => {
+                match self.custom.iter_mut().find(|(c, _)| *c == custom) {
+                    Some((_, count)) => *count += 1,
+                    None => self.custom.push((custom, 1)),
+                }
+            }
+            Step::UseIngredient(ingredient) => {
+                self.ingredients[Action::AddIngredient(ingredient).index()] += 1
+            }
+            Step::UseNetherWart => self.nether_wart += 1,
+        }
+    }
+}
+
+impl fmt::Display for Materials {
+    /// Lists the items that are used, e.g. "2 Water Bucket, 1 Glass Bottle, 1 Spider Eye".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut counts = vec![
+            (self.water_buckets, Step::UseWaterBucket),
+            (self.glass_bottles, Step::UseGlassBottle),
+        ];
+        for &ingredient in PotionIngredient::ALL.iter() {
+            counts.push((self.ingredient(ingredient), Step::UseIngredient(ingredient)));
+        }
+        counts.push((self.nether_wart, Step::UseNetherWart));
+        for &(custom, count) in &self.custom {
+            counts.push((count, Step::UseIngredient(PotionIngredient::Custom(custom))));
+        }
+        counts.retain(|&(count, _)| count > 0);
+        for (i, (count, step)) in counts.into_iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{} {}", count, step.item_name())?;
+        }
+        Ok(())
+    }
+}
+
+impl Recipe {
+    /// Counts the items the steps of `expand` use.
+    pub fn materials(&self) -> Materials {
+        let mut materials = Materials::default();
+        for step in self.expand() {
+            materials.add(step);
+        }
+        materials
+    }
+
+    /// Spells the recipe out as clicks, starting with an empty cauldron.
+    ///
+    /// The cauldron is filled with a water bucket first, and every "W" takes a bottle out to make
+    /// room for the water bucket, so the cauldron is full when the recipe is done.
+    pub fn expand(&self) -> Vec<Step> {
+        let mut cauldron = CauldronState::empty();
+        let mut steps = vec![Step::UseWaterBucket];
+        cauldron
+            .add_water_bucket()
+            .expect("an empty cauldron has room for water");
+        for &action in &self.0 {
+            let step = match action {
+                Action::AddIngredient(ingredient) => Step::UseIngredient(ingredient),
+                Action::Dilute => {
+                    if cauldron.level == MAX_LEVEL {
+                        cauldron
+                            .take_bottle()
+                            .expect("a full cauldron has a bottle");
+                        steps.push(Step::UseGlassBottle);
+                    }
+                    Step::UseWaterBucket
+                }
+                Action::AddNetherWart => Step::UseNetherWart,
+            };
+            cauldron.apply(action).expect("the cauldron is never empty");
+            steps.push(step);
+        }
+        steps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Materials, Step};
+    use crate::PotionIngredient::{FermentedSpiderEye, SpiderEye};
+    use crate::Recipe;
+
+    #[test]
+    fn water_takes_a_bottle_first() {
+        let recipe: Recipe = "EWFN".parse().unwrap();
+        assert_eq!(
+            recipe.expand(),
+            vec![
+                Step::UseWaterBucket,
+                Step::UseIngredient(SpiderEye),
+                Step::UseGlassBottle,
+                Step::UseWaterBucket,
+                Step::UseIngredient(FermentedSpiderEye),
+                Step::UseNetherWart,
+            ]
+        );
+        assert_eq!(
+            Step::UseGlassBottle.to_string(),
+            "Use a Glass Bottle on the cauldron"
+        );
+        assert_eq!(
+            Step::UseIngredient(FermentedSpiderEye).to_string(),
+            "Use Fermented Spider Eye on the cauldron"
+        );
+    }
+
+    #[test]
+    fn materials_count_every_click() {
+        let recipe: Recipe = "EWFNWN".parse().unwrap();
+        let materials = recipe.materials();
+        assert_eq!(
+            materials,
+            Materials {
+                water_buckets: 3,
+                glass_bottles: 2,
+                ingredients: [0, 0, 1, 1, 0, 0],
+                nether_wart: 2,
+                custom: Vec::new(),
+            }
+        );
+        assert_eq!(materials.ingredient(SpiderEye), 1);
+        assert_eq!(materials.consumed(), 6);
+        assert_eq!(
+            materials.to_string(),
+            "3 Water Bucket, 2 Glass Bottle, 1 Spider Eye, 1 Fermented Spider Eye, 2 Nether Wart"
+        );
+    }
+}
@@ -0,0 +1,71 @@
+//! Every action applied to every liquid, computed ahead of time for searches that take many steps.
+
+use crate::{Action, LiquidData, PotionIngredient};
+
+/// The result of every action on every 15-bit liquid, 8 × 32768 dvs in 512 KiB.
+#[derive(Clone, Debug)]
+pub struct TransitionTable {
+    /// next[dv][action.index()] = the liquid after taking that action
+    next: Vec<[LiquidData; 8]>,
+}
+
+impl TransitionTable {
+    /// Applies every action to every liquid.
+    pub fn new() -> Self {
+        let next = LiquidData::all()
+            .map(|ld| {
+                let mut row = [LiquidData::default(); 8];
+                for (next, action) in row.iter_mut().zip(Action::ALL.iter()) {
+                    *next = action.apply_to(ld);
+                }
+                row
+            })
+            .collect();
+        TransitionTable { next }
+    }
+
+    /// Looks up the result of taking `action` on `ld`, the same as `Action::apply_to`.
+    ///
+    /// Custom ingredients aren't in the table, so they are applied directly.
+    ///
+    /// # Panics
+    ///
+    /// If `ld` has any bit above the 15 the game uses set.
+    pub fn apply(&self, action: Action, ld: LiquidData) -> LiquidData {
+        match action {
+            Action::AddIngredient(PotionIngredient::Custom(_)) => action.apply_to(ld),
+            _ => self.next[ld.0 as usize][action.index()],
+        }
+    }
+
+    /// The results of all actions on `ld`, in the order of `Action::ALL`.
+    ///
+    /// # Panics
+    ///
+    /// If `ld` has any bit above the 15 the game uses set.
+    pub fn successors(&self, ld: LiquidData) -> &[LiquidData; 8] {
+        &self.next[ld.0 as usize]
+    }
+}
+
+impl Default for TransitionTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Action, LiquidData, TransitionTable};
+
+    #[test]
+    fn table_matches_actions() {
+        let table = TransitionTable::new();
+        for ld in LiquidData::all() {
+            for (i, &action) in Action::ALL.iter().enumerate() {
+                assert_eq!(table.apply(action, ld), action.apply_to(ld));
+                assert_eq!(table.successors(ld)[i], action.apply_to(ld));
+            }
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! Checking recipes that were written down elsewhere, e.g. in a community database.
+
+use crate::{Action, LiquidData, Recipe};
+use std::error::Error;
+use std::fmt;
+
+/// Why a recipe doesn't brew what it was claimed to, see `verify_recipe`.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum VerifyError {
+    /// The liquid after `step` steps isn't the claimed one. `action` is the step that was taken
+    /// last, `None` if the recipe is empty.
+    WrongLiquid {
+        step: usize,
+        action: Option<Action>,
+        expected: LiquidData,
+        actual: LiquidData,
+    },
+    /// The number of claimed liquids is neither one nor the number of steps.
+    WrongLength { steps: usize, claimed: usize },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            VerifyError::WrongLiquid {
+                step,
+                action: Some(action),
+                expected,
+                actual,
+            } => write!(
+                f,
+                "step {} ({}) gives {}, not {}",
+                step, action, actual.0, expected.0
+            ),
+            VerifyError::WrongLiquid {
+                expected, actual, ..
+            } => write!(
+                f,
+                "the recipe is empty, so it gives {}, not {}",
+                actual.0, expected.0
+            ),
+            VerifyError::WrongLength { steps, claimed } => write!(
+                f,
+                "{} liquids were claimed for a recipe with {} steps",
+                claimed, steps
+            ),
+        }
+    }
+}
+
+impl Error for VerifyError {}
+
+/// Checks that `recipe` brews what is claimed when started from `start`.
+///
+/// `claimed` is either just the final liquid, or the liquid after every step. In the latter case
+/// the first step that goes wrong is reported, otherwise the last one.
+pub fn verify_recipe(
+    start: LiquidData,
+    recipe: &Recipe,
+    claimed: &[LiquidData],
+) -> Result<(), VerifyError> {
+    let steps = recipe.0.len();
+    let states: Vec<(Action, LiquidData)> = recipe.states(start).collect();
+    let checked = if claimed.len() == steps {
+        0
+    } else if claimed.len() == 1 {
+        steps.saturating_sub(1)
+    } else {
+        return Err(VerifyError::WrongLength {
+            steps,
+            claimed: claimed.len(),
+        });
+    };
+    if steps == 0 {
+        return match claimed.first() {
+            Some(&expected) if expected != start => Err(VerifyError::WrongLiquid {
+                step: 0,
+                action: None,
+                expected,
+                actual: start,
+            }),
+            _ => Ok(()),
+        };
+    }
+    for (i, (&(action, actual), &expected)) in states[checked..].iter().zip(claimed).enumerate() {
+        if actual != expected {
+            return Err(VerifyError::WrongLiquid {
+                step: checked + i + 1,
+                action: Some(action),
+                expected,
+                actual,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{verify_recipe, VerifyError};
+    use crate::fixtures::{self, SPIDER_EYE, SPIDER_EYE_WART, WATER};
+    use crate::{Action, LiquidData, Recipe};
+
+    #[test]
+    fn fixture_recipes_verify() {
+        for fixture in fixtures::ALL.iter() {
+            if let Some(letters) = fixture.recipe {
+                let recipe: Recipe = letters.parse().unwrap();
+                assert_eq!(
+                    verify_recipe(WATER.liquid, &recipe, &[fixture.liquid]),
+                    Ok(())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn the_first_wrong_step_is_reported() {
+        let recipe: Recipe = "EN".parse().unwrap();
+        let states = [SPIDER_EYE.liquid, SPIDER_EYE_WART.liquid];
+        assert_eq!(verify_recipe(WATER.liquid, &recipe, &states), Ok(()));
+
+        let wrong = [LiquidData(1185), SPIDER_EYE_WART.liquid];
+        assert_eq!(
+            verify_recipe(WATER.liquid, &recipe, &wrong),
+            Err(VerifyError::WrongLiquid {
+                step: 1,
+                action: Some(recipe.0[0]),
+                expected: LiquidData(1185),
+                actual: SPIDER_EYE.liquid,
+            })
+        );
+        let err = verify_recipe(WATER.liquid, &recipe, &[SPIDER_EYE.liquid]).unwrap_err();
+        assert_eq!(
+            err,
+            VerifyError::WrongLiquid {
+                step: 2,
+                action: Some(Action::AddNetherWart),
+                expected: SPIDER_EYE.liquid,
+                actual: SPIDER_EYE_WART.liquid,
+            }
+        );
+        assert_eq!(err.to_string(), "step 2 (N) gives 1088, not 1184");
+    }
+
+    #[test]
+    fn claims_must_fit_the_recipe() {
+        let recipe: Recipe = "SEN".parse().unwrap();
+        assert_eq!(
+            verify_recipe(WATER.liquid, &recipe, &[LiquidData(1), LiquidData(2)]),
+            Err(VerifyError::WrongLength {
+                steps: 3,
+                claimed: 2
+            })
+        );
+        let empty = Recipe::default();
+        assert_eq!(verify_recipe(WATER.liquid, &empty, &[]), Ok(()));
+        assert_eq!(verify_recipe(WATER.liquid, &empty, &[WATER.liquid]), Ok(()));
+        assert!(matches!(
+            verify_recipe(WATER.liquid, &empty, &[LiquidData(1)]),
+            Err(VerifyError::WrongLiquid {
+                step: 0,
+                action: None,
+                ..
+            })
+        ));
+    }
+}
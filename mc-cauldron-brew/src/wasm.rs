@@ -0,0 +1,82 @@
+//! Bindings for JavaScript, so that brewing calculators in the browser can use the crate.
+//!
+//! Liquids are passed as their damage value and recipes and actions in letter notation, e.g.
+//! "WEFN". Functions return `undefined` for input they can't make sense of.
+//!
+//! The crate is only built as an rlib, since a cdylib can't be built without `std`. Build the
+//! module with `cargo rustc -p mc-cauldron-brew --release --features wasm --target
+//! wasm32-unknown-unknown --crate-type cdylib` and generate the JavaScript glue with the
+//! `wasm-bindgen` CLI.
+
+use crate::solver;
+use crate::{Action, LiquidData, Recipe};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Calculates the result of taking the action with this letter, e.g. 'E' for a spider eye.
+#[wasm_bindgen(js_name = applyAction)]
+pub fn apply_action(dv: u16, letter: char) -> Option<u16> {
+    Action::from_letter(letter).map(|action| action.apply_to(LiquidData(dv)).0)
+}
+
+/// Calculates the result of adding a water bucket.
+#[wasm_bindgen]
+pub fn dilute(dv: u16) -> u16 {
+    LiquidData(dv).dilute().0
+}
+
+/// Calculates the result of adding a nether wart.
+#[wasm_bindgen(js_name = applyWart)]
+pub fn apply_wart(dv: u16) -> u16 {
+    LiquidData(dv).apply_wart().0
+}
+
+/// Calculates what a recipe brews from the liquid with damage value `start`.
+#[wasm_bindgen]
+pub fn brew(recipe: &str, start: u16) -> Option<u16> {
+    let recipe: Recipe = recipe.parse().ok()?;
+    Some(recipe.apply(LiquidData(start)).0)
+}
+
+/// The display name of a potion, e.g. "Potion of Healing".
+#[wasm_bindgen]
+pub fn name(dv: u16) -> String {
+    LiquidData(dv).name()
+}
+
+/// The color of the liquid as 0xRRGGBB.
+#[wasm_bindgen]
+pub fn color(dv: u16) -> u32 {
+    LiquidData(dv).color()
+}
+
+/// The effects of a potion the way their tooltips read, e.g. "Poison II (1:30)".
+#[wasm_bindgen]
+pub fn effects(dv: u16) -> Vec<String> {
+    LiquidData(dv)
+        .effect_details()
+        .iter()
+        .map(|effect| effect.to_string())
+        .collect()
+}
+
+/// Finds the shortest recipe from `start` to `target`.
+#[wasm_bindgen]
+pub fn solve(start: u16, target: u16) -> Option<String> {
+    solver::breadth_first_from(LiquidData(start), LiquidData(target)).map(|r| r.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn bindings_match_the_crate() {
+        assert_eq!(super::apply_action(0, 'F'), Some(16896));
+        assert_eq!(super::apply_action(0, 'X'), None);
+        assert_eq!(super::brew("WEN", 0), Some(1088));
+        assert_eq!(super::brew("WEQ", 0), None);
+        assert_eq!(super::solve(0, 16896).as_deref(), Some("F"));
+        assert_eq!(
+            super::effects(16896),
+            ["Mining Fatigue (3:00)", "Poison II (1:30)"]
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! Ingredient sets read from a file, for servers with ingredients that the game doesn't have.
+//!
+//! The file is TOML, or JSON if its name ends in `.json`, with the same fields:
+//!
+//! ```toml
+//! # Set to true to leave the six ingredients of the game out, so that their letters and names
+//! # can be reused
+//! replace = false
+//!
+//! [[ingredients]]
+//! name = "Pixie Dust"
+//! letter = "P"
+//! bits = [2, 12]
+//! # How much taking it costs the solver, 1 if left out like for every other action
+//! cost = 3
+//! ```
+//!
+//! Names become CSV columns, so they can't contain commas, quotes or line breaks, and can't give
+//! the same column as another action or one of the other columns.
+
+use crate::{action_column, LEADING_COLUMNS, TRAILING_COLUMNS};
+use mc_cauldron_brew::{Action, CustomIngredient, IngredientRegistry, PotionIngredient};
+use serde::Deserialize;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IngredientFile {
+    #[serde(default)]
+    replace: bool,
+    ingredients: Vec<IngredientEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IngredientEntry {
+    name: String,
+    letter: char,
+    bits: Vec<u8>,
+    #[serde(default = "default_cost")]
+    cost: u32,
+}
+
+fn default_cost() -> u32 {
+    1
+}
+
+/// The actions an ingredient file allows, and what its ingredients cost.
+pub struct IngredientSet {
+    pub registry: IngredientRegistry,
+    costs: Vec<(CustomIngredient, u32)>,
+}
+
+impl IngredientSet {
+    /// Reads an ingredient file, see the module documentation.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let json = path.extension().is_some_and(|e| e == "json");
+        Self::parse(&text, json).map_err(|e| format!("{}: {}", path.display(), e).into())
+    }
+
+    /// Parses the contents of an ingredient file, which are JSON if `json` is set, otherwise TOML.
+    fn parse(text: &str, json: bool) -> Result<Self, String> {
+        let file = if json {
+            serde_json::from_str(text).map_err(|e| e.to_string())?
+        } else {
+            toml::from_str(text).map_err(|e| e.to_string())?
+        };
+        Self::from_file(file)
+    }
+
+    fn from_file(file: IngredientFile) -> Result<Self, String> {
+        let mut set = IngredientSet {
+            registry: if file.replace {
+                IngredientRegistry::without_builtins()
+            } else {
+                IngredientRegistry::new()
+            },
+            costs: Vec::new(),
+        };
+        for entry in file.ingredients {
+            if let Some(bit) = entry.bits.iter().find(|&&bit| bit >= 15) {
+                return Err(format!(
+                    "{} sets bit {}, but liquids only have 15 bits",
+                    entry.name, bit
+                ));
+            }
+            // custom ingredients borrow their name and bits for good, and the set is only loaded
+            // once, so leaking them is fine
+            let name: &'static str = Box::leak(entry.name.into_boxed_str());
+            let bits: &'static [u8] = Box::leak(entry.bits.into_boxed_slice());
+            let ingredient = CustomIngredient::new(name, entry.letter, bits);
+            check_column(ingredient, &set.registry.actions())?;
+            set.registry
+                .register(ingredient)
+                .map_err(|e| format!("can't add {}: {}", name, e))?;
+            set.costs.push((ingredient, entry.cost));
+        }
+        Ok(set)
+    }
+
+    /// The cost of taking an action: what the file says for its ingredients, otherwise 1.
+    pub fn cost(&self, action: Action) -> u32 {
+        match action {
+            Action::AddIngredient(PotionIngredient::Custom(ingredient)) => self
+                .costs
+                .iter()
+                .find(|&&(i, _)| i == ingredient)
+                .map_or(1, |&(_, cost)| cost),
+            _ => 1,
+        }
+    }
+}
+
+/// Checks that the name of `ingredient` can be its CSV column next to those of `actions`.
+fn check_column(ingredient: CustomIngredient, actions: &[Action]) -> Result<(), String> {
+    let name = ingredient.name();
+    if name.contains([',', '"', '\n', '\r']) {
+        return Err(format!(
+            "{:?} can't be a CSV column, names can't contain commas, quotes or line breaks",
+            name
+        ));
+    }
+    let column = action_column(Action::AddIngredient(PotionIngredient::Custom(ingredient)));
+    let taken = LEADING_COLUMNS
+        .iter()
+        .chain(TRAILING_COLUMNS.iter())
+        .map(|column| column.to_string())
+        .chain(actions.iter().map(|&action| action_column(action)))
+        .any(|taken| taken == column);
+    if taken {
+        return Err(format!(
+            "{} would be the CSV column {}, which is taken",
+            name, column
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IngredientSet;
+    use mc_cauldron_brew::{Action, PotionIngredient};
+
+    const PIXIE_DUST: &str = r#"
+        [[ingredients]]
+        name = "Pixie Dust"
+        letter = "P"
+        bits = [2, 12]
+        cost = 3
+
+        [[ingredients]]
+        name = "Salt"
+        letter = "T"
+        bits = [4]
+    "#;
+
+    #[test]
+    fn toml_files_are_read() {
+        let set = IngredientSet::parse(PIXIE_DUST, false).unwrap();
+        let pixie_dust = set.registry.action('P').unwrap();
+        let salt = set.registry.action('T').unwrap();
+        assert_eq!(set.registry.actions().len(), 10);
+        assert_eq!(set.cost(pixie_dust), 3);
+        // the cost defaults to 1, like for the actions of the game
+        assert_eq!(set.cost(salt), 1);
+        assert_eq!(set.cost(Action::AddNetherWart), 1);
+        assert_eq!(set.registry.ingredients()[0].added_bits(), [2, 12]);
+    }
+
+    #[test]
+    fn json_files_are_read() {
+        let json = r#"{"ingredients": [{"name": "Pixie Dust", "letter": "P", "bits": [2, 12], "cost": 3}]}"#;
+        let set = IngredientSet::parse(json, true).unwrap();
+        assert_eq!(set.cost(set.registry.action('P').unwrap()), 3);
+        assert!(IngredientSet::parse(json, false).is_err());
+    }
+
+    #[test]
+    fn bits_beyond_the_liquid_are_rejected() {
+        let toml = "[[ingredients]]\nname = \"Broken\"\nletter = \"X\"\nbits = [15]\n";
+        let err = IngredientSet::parse(toml, false).err().unwrap();
+        assert_eq!(err, "Broken sets bit 15, but liquids only have 15 bits");
+    }
+
+    #[test]
+    fn letters_of_the_game_are_taken() {
+        let toml = "[[ingredients]]\nname = \"Salt\"\nletter = \"S\"\nbits = [4]\n";
+        let err = IngredientSet::parse(toml, false).err().unwrap();
+        assert_eq!(err, "can't add Salt: the letter 'S' is already used");
+
+        let set = IngredientSet::parse(&format!("replace = true\n{}", toml), false).unwrap();
+        let salt = set.registry.action('S').unwrap();
+        assert_ne!(salt, Action::AddIngredient(PotionIngredient::Sugar));
+        assert_eq!(set.registry.actions().len(), 3);
+    }
+
+    #[test]
+    fn names_must_make_csv_columns() {
+        let named = |name: &str| {
+            format!(
+                "[[ingredients]]\nname = {:?}\nletter = \"X\"\nbits = [4]\n",
+                name
+            )
+        };
+        for name in ["Salt, coarse", "\"Salt\"", "Salt\nPepper"].iter() {
+            let err = IngredientSet::parse(&named(name), false).err().unwrap();
+            assert!(err.contains("can't be a CSV column"), "{}", err);
+        }
+        let err = IngredientSet::parse(&named("Nether Wart"), false)
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            "Nether Wart would be the CSV column nether_wart, which is taken"
+        );
+        assert!(IngredientSet::parse(&named("sugar"), false).is_err());
+        assert!(IngredientSet::parse(&named("Glass Bottles"), false).is_err());
+        // without the ingredients of the game, their columns are free
+        assert!(
+            IngredientSet::parse(&format!("replace = true\n{}", named("Sugar")), false).is_ok()
+        );
+
+        let twice = format!(
+            "{}[[ingredients]]\nname = \"Pixie_Dust\"\nletter = \"Y\"\nbits = [5]\n",
+            PIXIE_DUST
+        );
+        assert!(IngredientSet::parse(&twice, false).is_err());
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        let toml = "[[ingredients]]\nname = \"Salt\"\nletter = \"T\"\nbits = [4]\ncolor = 3\n";
+        assert!(IngredientSet::parse(toml, false).is_err());
+    }
+}
@@ -1,115 +1,838 @@
-use mc_cauldron_brew::PotionIngredient::{
-    BlazePowder, FermentedSpiderEye, GhastTear, MagmaCream, SpiderEye, Sugar,
+use clap::{Parser, Subcommand, ValueEnum};
+use mc_cauldron_brew::graph;
+use mc_cauldron_brew::solver::{self, ActionCosts, Constraints, RecipeTable, SearchStats};
+use mc_cauldron_brew::{
+    effect_classes, verify_recipe, Action, LiquidData, PotionEffect, PotionIngredient, Recipe,
+    TransitionTable,
 };
-use mc_cauldron_brew::{LiquidData, PotionIngredient};
-use std::collections::VecDeque;
 use std::error::Error;
-use std::fs::File;
-use std::io::Write;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
-/// Represents one interaction with a cauldron
-#[derive(Eq, PartialEq, Copy, Clone, Hash, Debug)]
-enum Action {
-    AddIngredient(PotionIngredient),
-    Dilute,
-    AddNetherWart,
+mod ingredients;
+mod world;
+
+use crate::ingredients::IngredientSet;
+
+/// A toolkit for brewing potions in cauldrons. Without a command, writes the full table to
+/// results.txt.
+#[derive(Parser)]
+struct Args {
+    /// Don't report progress or summaries on stderr.
+    #[arg(long, short, global = true)]
+    quiet: bool,
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    // solutions[dv] = actions to produce potion with that dv.
-    let mut solutions: Vec<Option<Vec<Action>>> = vec![None; 32768];
-    let mut queue: VecDeque<(Vec<Action>, LiquidData)> = VecDeque::new();
-
-    // Add the starting potion (plain water)
-    solutions[LiquidData::default().0 as usize] = Some(Vec::new());
-    queue.push_back((Vec::new(), LiquidData::default()));
-
-    // Perform a BFS (breadth-first search)
-    while !queue.is_empty() {
-        let mut next_queue = VecDeque::new();
-        // for every state in the queue
-        for (prev_actions, prev_state) in queue.into_iter() {
-            // check all possible actions to take from there
-            for action in ALL_ACTIONS.iter() {
-                let state = action.apply_to(prev_state);
-                // if that action leads to a new potion
-                if solutions[state.0 as usize].is_none() {
-                    // save the steps to get there and add it to the next queue
-                    let mut actions = prev_actions.clone();
-                    actions.push(*action);
-                    next_queue.push_back((actions.clone(), state));
-                    solutions[state.0 as usize] = Some(actions);
-                }
+#[derive(Subcommand)]
+enum Command {
+    /// Find the shortest recipe for a liquid or for a set of effects.
+    Solve(SolveArgs),
+    /// Show what a liquid is and how to brew it.
+    Decode {
+        /// The damage value of the liquid, in decimal, hex ("0x4200") or binary ("0b1000...").
+        #[arg(value_parser = parse_liquid)]
+        dv: u16,
+        /// Look the recipe up in a table written by "table --format binary" instead of searching.
+        #[arg(long, value_name = "FILE")]
+        table: Option<PathBuf>,
+    },
+    /// Show every liquid a recipe brews along the way and what it ends with.
+    Simulate {
+        /// The recipe in letter notation, e.g. "WEFN".
+        recipe: Recipe,
+        /// Brew from the liquid with this damage value instead of water.
+        #[arg(long, value_name = "DV", value_parser = parse_liquid)]
+        start: Option<u16>,
+        /// List the clicks that brew the recipe in-game instead, starting with an empty cauldron.
+        #[arg(long, conflicts_with = "start")]
+        steps: bool,
+    },
+    /// Find the shortest recipe for every liquid and write them to a file.
+    Table(TableArgs),
+    /// Read one dv or recipe per line from stdin and write one tab-separated result per line.
+    ///
+    /// Lines that can't be parsed or have no result produce "-", and an error on stderr.
+    Batch {
+        #[arg(value_enum)]
+        mode: BatchMode,
+    },
+    /// Check that a recipe brews the liquid it should, and whether it is as short as possible.
+    Verify {
+        /// The recipe in letter notation, e.g. "WEFN".
+        recipe: Recipe,
+        /// The damage value the recipe should brew.
+        #[arg(value_parser = parse_liquid)]
+        dv: u16,
+    },
+    /// Show how much each action matters: what can't be brewed without it and how much longer the
+    /// other recipes get.
+    ///
+    /// "longer by" is the average number of extra steps over the liquids that can still be brewed.
+    Ablation,
+    /// List every liquid that can't be brewed, and how many there are.
+    Unreachable {
+        /// Brew from the liquid with this damage value instead of water.
+        #[arg(long, value_name = "DV", value_parser = parse_liquid)]
+        start: Option<u16>,
+    },
+    /// Plan one brew that fills a bottle of each of several potions, with as few clicks as
+    /// possible.
+    Plan {
+        /// The damage values of the potions, at most 6.
+        #[arg(required = true, value_parser = parse_liquid)]
+        dvs: Vec<u16>,
+    },
+    /// List every cauldron in a saved world with its liquid and effects.
+    ///
+    /// Prints one tab-separated line per cauldron: position, damage value, name, effects and, with
+    /// --recipes, the shortest recipe. Chunks that can't be read are reported on stderr and
+    /// skipped.
+    Scan {
+        /// The world directory, a directory of region files or a single region file.
+        world: PathBuf,
+        /// Also print the shortest recipe that brews each liquid from water.
+        #[arg(long)]
+        recipes: bool,
+    },
+}
+
+#[derive(clap::Args)]
+struct SolveArgs {
+    /// The damage value of the liquid to brew.
+    #[arg(required_unless_present_any = ["effect", "without"], conflicts_with_all = ["effect", "without"], value_parser = parse_liquid)]
+    target: Option<u16>,
+    /// Brew from the liquid with this damage value instead of water.
+    #[arg(long, value_name = "DV", requires = "target", conflicts_with_all = ["count", "ban", "limit", "no_dilute"], value_parser = parse_liquid)]
+    start: Option<u16>,
+    /// Print this many of the shortest recipes, shortest first.
+    #[arg(long, value_name = "K", requires = "target", value_parser = clap::value_parser!(u32).range(1..))]
+    count: Option<u32>,
+    /// Never take this action, given as its recipe letter (can be repeated).
+    #[arg(long, value_name = "ACTION", requires = "target", conflicts_with = "count", value_parser = parse_action)]
+    ban: Vec<Action>,
+    /// Take an action at most N times, e.g. "N=2" (can be repeated).
+    #[arg(long, value_name = "ACTION=N", requires = "target", conflicts_with = "count", value_parser = parse_limit)]
+    limit: Vec<(Action, u32)>,
+    /// Never dilute.
+    #[arg(long, requires = "target", conflicts_with = "count")]
+    no_dilute: bool,
+    /// Search for the shortest recipe whose potion has this effect (can be repeated).
+    #[arg(long, value_name = "EFFECT")]
+    effect: Vec<PotionEffect>,
+    /// Skip potions that have this effect (can be repeated).
+    #[arg(long, value_name = "EFFECT")]
+    without: Vec<PotionEffect>,
+    /// Also use the ingredients in this TOML or JSON file, or only those if it says "replace",
+    /// and find the cheapest recipe with their costs.
+    #[arg(long, value_name = "FILE", requires = "target", conflicts_with_all = ["count", "ban", "limit", "no_dilute"])]
+    ingredients: Option<PathBuf>,
+}
+
+#[derive(clap::Args)]
+struct TableArgs {
+    /// How to write the table, instead of going by the extension of every --out.
+    #[arg(long, value_enum)]
+    format: Option<Format>,
+    /// Where to write the table, instead of results.txt, results.csv and so on (can be repeated).
+    ///
+    /// The format is taken from the extension: .txt, .csv, .bin, .dot or .give.
+    #[arg(long, short, value_name = "FILE")]
+    out: Vec<PathBuf>,
+    /// Brew from the liquid with this damage value instead of water.
+    #[arg(long, value_name = "DV", value_parser = parse_liquid)]
+    start: Option<u16>,
+    /// With --format dot, only include liquids close to this one.
+    #[arg(long, value_name = "DV", value_parser = parse_liquid)]
+    around: Option<u16>,
+    /// With --around, how many actions away liquids may be, in either direction.
+    #[arg(long, value_name = "N", requires = "around", default_value_t = 2)]
+    radius: usize,
+    /// Only write the shortest recipe for each set of effects, to text, csv and give outputs. With
+    /// --ingredients, the cheapest one with the costs in the file.
+    #[arg(long)]
+    dedup: bool,
+    /// With --format give, the player who gets the potions.
+    #[arg(long, value_name = "NAME", default_value = "<player>")]
+    player: String,
+    /// After the search, print how it went level by level.
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    stats: Option<StatsFormat>,
+    /// Also use the ingredients in this TOML or JSON file, or only those if it says "replace",
+    /// and write the cheapest recipes with their costs.
+    #[arg(long, value_name = "FILE", conflicts_with = "stats")]
+    ingredients: Option<PathBuf>,
+}
+
+impl Default for TableArgs {
+    /// What `table` without any arguments gets, so that the defaults are only set once, above.
+    fn default() -> Self {
+        match Args::parse_from(["mc-cauldron-bruteforce", "table"]).command {
+            Some(Command::Table(args)) => args,
+            _ => unreachable!("\"table\" parses as the table command"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, ValueEnum)]
+enum BatchMode {
+    /// dv -> dv, name, color, effects and shortest recipe
+    Decode,
+    /// dv -> shortest recipe
+    Solve,
+    /// recipe -> dv and name of what it brews
+    Simulate,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, ValueEnum)]
+enum Format {
+    /// One line per liquid in results.txt: dv, recipe, name and color
+    Text,
+    /// A spreadsheet in results.csv, with how often each action is taken and the buckets and
+    /// bottles that takes
+    Csv,
+    /// A fixed-layout lookup table in results.bin, with one 16 byte record per dv
+    Binary,
+    /// The graph between the liquids in Graphviz format, in results.dot
+    Dot,
+    /// One /give command per liquid in results.give, for handing potions out on a server
+    Give,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, ValueEnum)]
+enum StatsFormat {
+    /// A table for reading
+    Text,
+    /// A JSON object for comparing runs with other tools
+    Json,
+}
+
+impl Format {
+    fn file_name(self) -> &'static str {
+        match self {
+            Format::Text => "results.txt",
+            Format::Csv => "results.csv",
+            Format::Binary => "results.bin",
+            Format::Dot => "results.dot",
+            Format::Give => "results.give",
+        }
+    }
+
+    fn from_extension(path: &Path) -> Option<Format> {
+        match path.extension()?.to_str()? {
+            "txt" => Some(Format::Text),
+            "csv" => Some(Format::Csv),
+            "bin" => Some(Format::Binary),
+            "dot" => Some(Format::Dot),
+            "give" => Some(Format::Give),
+            _ => None,
+        }
+    }
+}
+
+/// The CSV column for how often an action is taken, e.g. "spider_eye".
+fn action_column(action: Action) -> String {
+    let column = match action {
+        Action::AddIngredient(PotionIngredient::Sugar) => "sugar",
+        Action::AddIngredient(PotionIngredient::GhastTear) => "ghast_tear",
+        Action::AddIngredient(PotionIngredient::SpiderEye) => "spider_eye",
+        Action::AddIngredient(PotionIngredient::FermentedSpiderEye) => "fermented_spider_eye",
+        Action::AddIngredient(PotionIngredient::BlazePowder) => "blaze_powder",
+        Action::AddIngredient(PotionIngredient::MagmaCream) => "magma_cream",
+        Action::AddIngredient(PotionIngredient::Custom(ingredient)) => ingredient.name(),
+        // an ingredient that a later version of the library knows, named like in its Debug output
+        Action::AddIngredient(other) => return format!("{:?}", other).to_lowercase(),
+        Action::Dilute => "dilute",
+        Action::AddNetherWart => "nether_wart",
+    };
+    column.to_lowercase().replace(' ', "_")
+}
+
+fn write_text(writer: &mut impl Write, rows: &[(LiquidData, Recipe)]) -> io::Result<()> {
+    for (potion, recipe) in rows {
+        writeln!(
+            writer,
+            "{:05}, {}, {}, #{:06x}",
+            potion.0,
+            recipe,
+            potion.name(),
+            potion.color()
+        )?;
+    }
+    Ok(())
+}
+
+/// The CSV columns before those of the actions.
+const LEADING_COLUMNS: [&str; 5] = ["dv", "steps", "recipe", "name", "color"];
+/// The CSV columns after those of the actions.
+const TRAILING_COLUMNS: [&str; 2] = ["water_buckets", "glass_bottles"];
+
+/// Writes the table as CSV, with a column for how often each of `actions` is taken.
+fn write_csv(
+    writer: &mut impl Write,
+    rows: &[(LiquidData, Recipe)],
+    actions: &[Action],
+) -> io::Result<()> {
+    write!(writer, "{}", LEADING_COLUMNS.join(","))?;
+    for &action in actions {
+        write!(writer, ",{}", action_column(action))?;
+    }
+    writeln!(writer, ",{}", TRAILING_COLUMNS.join(","))?;
+    for (potion, recipe) in rows {
+        write!(
+            writer,
+            "{},{},{},{},#{:06x}",
+            potion.0,
+            recipe.0.len(),
+            recipe,
+            potion.name(),
+            potion.color()
+        )?;
+        for &action in actions {
+            let count = recipe.0.iter().filter(|&&a| a == action).count();
+            write!(writer, ",{}", count)?;
+        }
+        let materials = recipe.materials();
+        writeln!(
+            writer,
+            ",{},{}",
+            materials.water_buckets, materials.glass_bottles
+        )?;
+    }
+    Ok(())
+}
+
+/// The item id of potions, which take their damage value from the liquid they were filled from.
+const POTION_ID: u16 = 373;
+
+/// Writes a command that gives one potion of each liquid.
+///
+/// Item frames and commands that take NBT came after b1.9-pre2, so the damage value is the only
+/// way to pick the potion.
+fn write_give(
+    writer: &mut impl Write,
+    rows: &[(LiquidData, Recipe)],
+    player: &str,
+) -> io::Result<()> {
+    for (potion, _) in rows {
+        writeln!(writer, "/give {} {} 1 {}", player, POTION_ID, potion.0)?;
+    }
+    Ok(())
+}
+
+fn parse_action(s: &str) -> Result<Action, String> {
+    let mut chars = s.chars();
+    match (chars.next().and_then(Action::from_letter), chars.next()) {
+        (Some(action), None) => Ok(action),
+        _ => Err(format!("'{}' is not an action letter", s)),
+    }
+}
+
+/// Parses a damage value in decimal, or in hex or binary with a "0x" or "0b" prefix.
+fn parse_liquid(s: &str) -> Result<u16, String> {
+    let parsed = if let Some(hex) = s.strip_prefix("0x") {
+        u16::from_str_radix(hex, 16)
+    } else if let Some(binary) = s.strip_prefix("0b") {
+        u16::from_str_radix(binary, 2)
+    } else {
+        s.parse()
+    };
+    match parsed {
+        Ok(dv) if dv < 0x8000 => Ok(dv),
+        Ok(_) => Err(format!("{} is not a valid liquid", s)),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn parse_limit(s: &str) -> Result<(Action, u32), String> {
+    let (action, max) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected ACTION=N, got '{}'", s))?;
+    let max = max.parse().map_err(|e| format!("invalid limit: {}", e))?;
+    Ok((parse_action(action)?, max))
+}
+
+fn solve(args: SolveArgs) -> Result<(), Box<dyn Error>> {
+    let start = LiquidData(args.start.unwrap_or(0));
+    let dv = match args.target {
+        Some(dv) => dv,
+        None => {
+            let (potion, recipe) = solver::with_effects(&args.effect, &args.without)
+                .ok_or("no potion with these effects can be brewed from water")?;
+            println!("{:05}, {}, {}", potion.0, recipe, potion.name());
+            return Ok(());
+        }
+    };
+
+    let mut constraints = Some(Constraints::default());
+    for &action in &args.ban {
+        constraints = constraints.and_then(|c| c.ban(action));
+    }
+    for &(action, max) in &args.limit {
+        constraints = constraints.and_then(|c| c.limit(action, max));
+    }
+    if args.no_dilute {
+        constraints = constraints.and_then(|c| c.ban(Action::Dilute));
+    }
+    let constraints = constraints.ok_or("custom ingredients can't be banned or limited")?;
+
+    if let Some(path) = &args.ingredients {
+        let set = IngredientSet::load(path)?;
+        let table = RecipeTable::cheapest_using(start, &set.registry.actions(), |a| set.cost(a));
+        let recipe = table.recipe(LiquidData(dv)).ok_or_else(|| {
+            let from = args.start.map_or("water".to_string(), |dv| dv.to_string());
+            format!(
+                "{} can't be brewed from {} with these ingredients",
+                dv, from
+            )
+        })?;
+        println!("{}", recipe);
+        return Ok(());
+    }
+
+    let recipes = match args.count {
+        Some(k) => solver::k_shortest(LiquidData(dv), k as usize),
+        None if constraints != Constraints::default() => {
+            solver::constrained(LiquidData(dv), &constraints)
+                .into_iter()
+                .collect()
+        }
+        None => solver::breadth_first_from(start, LiquidData(dv))
+            .into_iter()
+            .collect(),
+    };
+    if recipes.is_empty() {
+        if constraints != Constraints::default() && solver::breadth_first(LiquidData(dv)).is_some()
+        {
+            return Err(format!("{} can't be brewed within these constraints", dv).into());
+        }
+        let from = args.start.map_or("water".to_string(), |dv| dv.to_string());
+        return Err(format!("{} can't be brewed from {}", dv, from).into());
+    }
+    for recipe in recipes {
+        println!("{}", recipe);
+    }
+    Ok(())
+}
+
+fn decode(dv: u16, table: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    let ld = LiquidData(dv);
+    let recipe = match table {
+        Some(path) => {
+            let bytes = fs::read(path)?;
+            if !solver::is_lookup_table(&bytes) {
+                return Err(format!("{} is not a lookup table", path.display()).into());
             }
+            solver::lookup(&bytes, ld)
         }
-        queue = next_queue;
+        None => solver::breadth_first(ld),
+    };
+
+    println!("{:05} = {:#06x} = {:#017b}", dv, dv, dv);
+    println!("{}, #{:06x}", ld.name(), ld.color());
+    for effect in ld.effect_details() {
+        println!("  {}", effect);
     }
+    match recipe {
+        Some(recipe) => println!("recipe: {}", recipe),
+        None => println!("can't be brewed from water"),
+    }
+    Ok(())
+}
 
-    // Write results to some file
-    let mut writer = File::create("results.txt")?;
-    // for (i, actions) in solutions.iter().enumerate() {
-    //     let actions = actions
-    //         .as_ref()
-    //         .map(|actions| format_actions(actions))
-    //         .unwrap_or("------".to_string());
-    //     writeln!(writer, "{:05}, {}", i, actions);
-    // }
-    for (i, actions) in solutions
-        .iter()
-        .enumerate()
-        .filter_map(|(i, a)| a.as_ref().map(|a| (i, a)))
+fn simulate(recipe: &Recipe, start: LiquidData) {
+    println!("     {:05}  {}", start.0, start.name());
+    for (step, (action, ld)) in recipe.states(start).enumerate() {
+        println!("{:>3} {} {:05}  {}", step + 1, action, ld.0, ld.name());
+    }
+    let result = recipe.apply(start);
+    println!();
+    println!(
+        "{:05}, {}, #{:06x}",
+        result.0,
+        result.name(),
+        result.color()
+    );
+    for effect in result.effect_details() {
+        println!("  {}", effect);
+    }
+    if start == LiquidData::default() {
+        println!("materials: {}", recipe.materials());
+    }
+}
+
+fn table(args: TableArgs, quiet: bool) -> Result<(), Box<dyn Error>> {
+    let start = LiquidData(args.start.unwrap_or(0));
+    let outputs = if args.out.is_empty() {
+        let format = args.format.unwrap_or(Format::Text);
+        vec![(PathBuf::from(format.file_name()), format)]
+    } else {
+        let mut outputs = Vec::new();
+        for path in args.out {
+            let format = args
+                .format
+                .or_else(|| Format::from_extension(&path))
+                .ok_or_else(|| {
+                    format!(
+                        "can't tell the format of {} from its extension, use --format",
+                        path.display()
+                    )
+                })?;
+            outputs.push((path, format));
+        }
+        outputs
+    };
+    if args.dedup
+        && outputs
+            .iter()
+            .any(|&(_, format)| format == Format::Binary || format == Format::Dot)
     {
-        writeln!(writer, "{:05}, {}", i, format_actions(actions))?;
+        return Err("--dedup only works with text, csv and give outputs".into());
+    }
+    if args.around.is_some() && outputs.iter().all(|&(_, format)| format != Format::Dot) {
+        return Err("--around needs an output in dot format".into());
     }
+    // Check this before any output is created, the lookup table has no room for custom ingredients
+    if args.ingredients.is_some() && outputs.iter().any(|&(_, format)| format == Format::Binary) {
+        return Err("--ingredients doesn't work with binary outputs".into());
+    }
+    let set = args
+        .ingredients
+        .as_deref()
+        .map(IngredientSet::load)
+        .transpose()?;
+    let actions = match &set {
+        Some(set) => set.registry.actions(),
+        None => Action::ALL.to_vec(),
+    };
+    let started = Instant::now();
+    let mut stats = SearchStats::start();
+    let table = match &set {
+        Some(set) => RecipeTable::cheapest_using(start, &actions, |a| set.cost(a)),
+        None => RecipeTable::build_with_progress(start, |progress| {
+            stats.record(progress);
+            if !quiet && progress.frontier > 0 {
+                eprintln!(
+                    "depth {:2}: {:5} liquids found, {:5} to continue from ({:.1?})",
+                    progress.depth,
+                    progress.discovered,
+                    progress.frontier,
+                    started.elapsed()
+                );
+            }
+        }),
+    };
 
-    writer.sync_all()?;
+    let rows = if args.dedup {
+        match &set {
+            Some(set) => table.cheapest_per_class(&|a| set.cost(a)),
+            None => table.cheapest_per_class(&ActionCosts::uniform()),
+        }
+    } else {
+        table.iter().collect()
+    };
+
+    // Write results to every file
+    for (path, format) in outputs {
+        let mut writer = BufWriter::new(File::create(path)?);
+        match format {
+            Format::Text => write_text(&mut writer, &rows)?,
+            Format::Csv => write_csv(&mut writer, &rows, &actions)?,
+            Format::Binary => table.write_lookup(&mut writer)?,
+            Format::Give => write_give(&mut writer, &rows, &args.player)?,
+            Format::Dot => {
+                let liquids = match args.around {
+                    Some(dv) => graph::neighborhood(LiquidData(dv), args.radius),
+                    None => table.iter().map(|(ld, _)| ld).collect(),
+                };
+                graph::write_dot(&mut writer, &liquids)?
+            }
+        }
+        writer.into_inner()?.sync_all()?;
+    }
+    if !quiet {
+        eprintln!(
+            "found {} solutions, at most {} steps long",
+            table.len(),
+            table.max_steps(),
+        );
+        if args.dedup {
+            eprintln!("wrote one for each of {} sets of effects", rows.len());
+        }
+    }
+    match args.stats {
+        Some(StatsFormat::Text) => print_stats(&stats),
+        Some(StatsFormat::Json) => println!("{}", stats_json(&stats)),
+        None => {}
+    }
+    Ok(())
+}
+
+fn print_stats(stats: &SearchStats) {
+    println!("depth  expanded  discovered  branching      time");
+    for level in &stats.levels {
+        println!(
+            "{:5}  {:8}  {:10}  {:9.3}  {:>8.1?}",
+            level.depth,
+            level.expanded,
+            level.discovered,
+            level.discovered as f64 / level.expanded as f64,
+            level.time
+        );
+    }
     println!(
-        "found {} solutions, at most {} steps long",
-        solutions.iter().filter(|s| s.is_some()).count(),
-        solutions
-            .iter()
-            .filter_map(|s| s.as_ref())
-            .map(|s| s.len())
-            .max()
-            .expect("There should be some answers"),
+        "total {:.1?}, peak queue {}, effective branching factor {:.3}",
+        stats.total_time(),
+        stats.peak_queue(),
+        stats.branching_factor()
     );
-    Ok(())
 }
 
-fn format_actions(actions: &Vec<Action>) -> String {
-    let action_names: Vec<_> = actions
+fn stats_json(stats: &SearchStats) -> serde_json::Value {
+    let levels: Vec<_> = stats
+        .levels
         .iter()
-        .map(|a| match a {
-            Action::AddIngredient(Sugar) => "S",
-            Action::AddIngredient(GhastTear) => "G",
-            Action::AddIngredient(SpiderEye) => "E",
-            Action::AddIngredient(FermentedSpiderEye) => "F",
-            Action::AddIngredient(BlazePowder) => "B",
-            Action::AddIngredient(MagmaCream) => "C",
-            Action::Dilute => "W",
-            Action::AddNetherWart => "N",
+        .map(|level| {
+            serde_json::json!({
+                "depth": level.depth,
+                "expanded": level.expanded,
+                "discovered": level.discovered,
+                "seconds": level.time.as_secs_f64(),
+            })
         })
         .collect();
-    action_names.join("")
-}
-
-const ALL_ACTIONS: [Action; 8] = [
-    Action::AddIngredient(Sugar),
-    Action::AddIngredient(GhastTear),
-    Action::AddIngredient(SpiderEye),
-    Action::AddIngredient(FermentedSpiderEye),
-    Action::AddIngredient(BlazePowder),
-    Action::AddIngredient(MagmaCream),
-    Action::Dilute,
-    Action::AddNetherWart,
-];
-
-impl Action {
-    pub fn apply_to(&self, ld: LiquidData) -> LiquidData {
-        match self {
-            Action::AddIngredient(ing) => ld.apply_ingredient(*ing),
-            Action::Dilute => ld.dilute(),
-            Action::AddNetherWart => ld.apply_wart(),
+    serde_json::json!({
+        "levels": levels,
+        "seconds": stats.total_time().as_secs_f64(),
+        "peak_queue": stats.peak_queue(),
+        "branching_factor": stats.branching_factor(),
+    })
+}
+
+fn batch(mode: BatchMode) -> Result<(), Box<dyn Error>> {
+    let table = RecipeTable::build();
+    let recipe_or_dash = |ld: LiquidData| match table.recipe(ld) {
+        Some(recipe) => recipe.to_string(),
+        None => "-".to_string(),
+    };
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut failed = false;
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        let input = line.trim();
+        let result = match mode {
+            BatchMode::Decode => parse_liquid(input).map(|dv| {
+                let ld = LiquidData(dv);
+                let effects: Vec<_> = ld.effect_details().iter().map(|e| e.to_string()).collect();
+                format!(
+                    "{:05}\t{}\t#{:06x}\t{}\t{}",
+                    dv,
+                    ld.name(),
+                    ld.color(),
+                    effects.join(", "),
+                    recipe_or_dash(ld)
+                )
+            }),
+            BatchMode::Solve => parse_liquid(input).map(|dv| recipe_or_dash(LiquidData(dv))),
+            BatchMode::Simulate => input
+                .parse::<Recipe>()
+                .map(|recipe| {
+                    let ld = recipe.apply(LiquidData::default());
+                    format!("{:05}\t{}", ld.0, ld.name())
+                })
+                .map_err(|e| e.to_string()),
+        };
+        match result {
+            Ok(result) => writeln!(out, "{}", result)?,
+            Err(e) => {
+                eprintln!("{}: {}", input, e);
+                failed = true;
+                writeln!(out, "-")?;
+            }
+        }
+    }
+    if failed {
+        return Err("some lines couldn't be processed".into());
+    }
+    Ok(())
+}
+
+fn verify(recipe: &Recipe, dv: u16) -> Result<(), Box<dyn Error>> {
+    let result = recipe.apply(LiquidData::default());
+    if verify_recipe(LiquidData::default(), recipe, &[LiquidData(dv)]).is_err() {
+        return Err(format!(
+            "{} brews {:05} ({}), not {:05} ({})",
+            recipe,
+            result.0,
+            result.name(),
+            dv,
+            LiquidData(dv).name()
+        )
+        .into());
+    }
+    println!("{} brews {:05} ({})", recipe, dv, result.name());
+    if let Some(shortest) = solver::breadth_first(result) {
+        if shortest.0.len() < recipe.0.len() {
+            println!(
+                "{} is shorter ({} instead of {} steps)",
+                shortest,
+                shortest.0.len(),
+                recipe.0.len()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn ablation() {
+    let water = LiquidData::default();
+    let full = RecipeTable::build();
+    let transitions = TransitionTable::new();
+    let classes = effect_classes(full.iter().map(|(ld, _)| ld)).len();
+    println!("without                   reachable   lost  lost effects  longer by  longest");
+    for &removed in Action::ALL.iter() {
+        let actions: Vec<Action> = Action::ALL
+            .iter()
+            .copied()
+            .filter(|&a| a != removed)
+            .collect();
+        let table = RecipeTable::build_with_transitions(&transitions, water, &actions);
+        let extra_steps: usize = table
+            .iter()
+            .map(|(ld, recipe)| recipe.0.len() - full.recipe(ld).unwrap().0.len())
+            .sum();
+        println!(
+            "{:24}  {:9}  {:5}  {:12}  {:9.2}  {:7}",
+            format!("{} ({})", action_column(removed), removed),
+            table.len(),
+            full.len() - table.len(),
+            classes - effect_classes(table.iter().map(|(ld, _)| ld)).len(),
+            extra_steps as f64 / table.len() as f64,
+            table.max_steps()
+        );
+    }
+}
+
+fn unreachable(start: LiquidData, quiet: bool) -> Result<(), Box<dyn Error>> {
+    let missing = graph::unreachable(start);
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for ld in &missing {
+        writeln!(out, "{:05}\t{}", ld.0, ld.name())?;
+    }
+    if !quiet {
+        eprintln!(
+            "{} of {} liquids can't be brewed from {:05}",
+            missing.len(),
+            0x8000,
+            start.0
+        );
+    }
+    Ok(())
+}
+
+fn plan(dvs: &[u16]) -> Result<(), Box<dyn Error>> {
+    let targets: Vec<LiquidData> = dvs.iter().copied().map(LiquidData).collect();
+    let plan = solver::plan_bottling(&targets)?;
+    let mut bottles = plan.bottles.iter().peekable();
+    for (i, step) in plan.steps.iter().enumerate() {
+        match bottles.next_if(|&&(_, at)| at == i) {
+            Some((ld, _)) => println!("{:>3}. {}: {:05} {}", i + 1, step, ld.0, ld.name()),
+            None => println!("{:>3}. {}", i + 1, step),
+        }
+    }
+    Ok(())
+}
+
+fn scan(world: &Path, recipes: bool, quiet: bool) -> Result<(), Box<dyn Error>> {
+    let files = world::region_files(world)?;
+    if files.is_empty() {
+        return Err(format!("found no region files in {}", world.display()).into());
+    }
+    let table = if recipes {
+        Some(RecipeTable::build())
+    } else {
+        None
+    };
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut count = 0;
+    for path in &files {
+        let cauldrons = world::cauldrons_in_region(path, |chunk, e| {
+            eprintln!("{}: skipping chunk {}: {}", path.display(), chunk, e)
+        })
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+        for cauldron in cauldrons {
+            let ld = cauldron.liquid;
+            let effects = ld
+                .effect_details()
+                .iter()
+                .map(|effect| effect.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            write!(
+                out,
+                "{} {} {}\t{:05}\t{}\t{}",
+                cauldron.x,
+                cauldron.y,
+                cauldron.z,
+                ld.0,
+                ld.name(),
+                effects
+            )?;
+            if let Some(table) = &table {
+                match table.recipe(ld) {
+                    Some(recipe) => write!(out, "\t{}", recipe)?,
+                    None => write!(out, "\t-")?,
+                }
+            }
+            writeln!(out)?;
+            count += 1;
+        }
+    }
+    if !quiet {
+        eprintln!("found {} cauldrons in {} region files", count, files.len());
+    }
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse();
+    let quiet = args.quiet;
+    match args
+        .command
+        .unwrap_or_else(|| Command::Table(TableArgs::default()))
+    {
+        Command::Solve(args) => solve(args),
+        Command::Decode { dv, table } => decode(dv, table.as_deref()),
+        Command::Simulate {
+            recipe,
+            start,
+            steps,
+        } => {
+            if steps {
+                for (i, step) in recipe.expand().iter().enumerate() {
+                    println!("{:>3}. {}", i + 1, step);
+                }
+            } else {
+                simulate(&recipe, LiquidData(start.unwrap_or(0)));
+            }
+            Ok(())
+        }
+        Command::Table(args) => table(args, quiet),
+        Command::Batch { mode } => batch(mode),
+        Command::Verify { recipe, dv } => verify(&recipe, dv),
+        Command::Ablation => {
+            ablation();
+            Ok(())
         }
+        Command::Unreachable { start } => unreachable(LiquidData(start.unwrap_or(0)), quiet),
+        Command::Plan { dvs } => plan(&dvs),
+        Command::Scan { world, recipes } => scan(&world, recipes, quiet),
     }
 }
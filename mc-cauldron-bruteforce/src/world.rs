@@ -0,0 +1,115 @@
+//! Finding the cauldrons in a saved world.
+//!
+//! Beta worlds store chunks in McRegion files (`.mcr`), which later versions convert to Anvil
+//! (`.mca`). Both use the same container: a table of 1024 chunk locations, then each chunk as a
+//! length, a compression type and a compressed NBT compound whose `Level` holds the
+//! `TileEntities`. A converted world keeps its old region files, so a directory with Anvil files
+//! is only scanned for those.
+
+use mc_cauldron_brew::nbt::{CauldronEntity, CauldronNbtError};
+use quartz_nbt::io::{read_nbt, Flavor};
+use quartz_nbt::{NbtCompound, NbtList};
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const SECTOR: usize = 4096;
+
+/// Lists the region files in `path` and every directory below it, in a stable order.
+///
+/// `path` may also be a single region file.
+pub fn region_files(path: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    if path.is_dir() {
+        collect_region_files(path, &mut files)?;
+    } else {
+        files.push(path.to_path_buf());
+    }
+    Ok(files)
+}
+
+fn collect_region_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort();
+    let has_extension = |path: &Path, ext| path.extension().is_some_and(|e| e == ext);
+    let extension = if entries.iter().any(|path| has_extension(path, "mca")) {
+        "mca"
+    } else {
+        "mcr"
+    };
+    for path in entries {
+        if path.is_dir() {
+            collect_region_files(&path, files)?;
+        } else if has_extension(&path, extension) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Reads every cauldron from a region file.
+///
+/// Chunks that can't be read are passed to `on_error` with their index in the region, and
+/// skipped.
+pub fn cauldrons_in_region(
+    path: &Path,
+    mut on_error: impl FnMut(usize, Box<dyn Error>),
+) -> io::Result<Vec<CauldronEntity>> {
+    let bytes = fs::read(path)?;
+    if bytes.len() < SECTOR {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "region file is shorter than its header",
+        ));
+    }
+    let mut cauldrons = Vec::new();
+    for (index, location) in bytes[..SECTOR].chunks_exact(4).enumerate() {
+        let offset = u32::from_be_bytes([0, location[0], location[1], location[2]]) as usize;
+        if offset == 0 {
+            continue;
+        }
+        match read_chunk(&bytes, offset * SECTOR).and_then(|chunk| cauldrons_in_chunk(&chunk)) {
+            Ok(found) => cauldrons.extend(found),
+            Err(e) => on_error(index, e),
+        }
+    }
+    Ok(cauldrons)
+}
+
+fn read_chunk(bytes: &[u8], start: usize) -> Result<NbtCompound, Box<dyn Error>> {
+    let header = bytes
+        .get(start..start + 5)
+        .ok_or("chunk starts past the end of the file")?;
+    let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    let data = length
+        .checked_sub(1)
+        .and_then(|length| bytes.get(start + 5..start + 5 + length))
+        .ok_or("chunk is cut off")?;
+    let flavor = match header[4] {
+        1 => Flavor::GzCompressed,
+        2 => Flavor::ZlibCompressed,
+        3 => Flavor::Uncompressed,
+        other => return Err(format!("unknown compression type {}", other).into()),
+    };
+    Ok(read_nbt(&mut &data[..], flavor)?.0)
+}
+
+fn cauldrons_in_chunk(chunk: &NbtCompound) -> Result<Vec<CauldronEntity>, Box<dyn Error>> {
+    let level = chunk.get::<_, &NbtCompound>("Level")?;
+    let tile_entities = match level.get::<_, &NbtList>("TileEntities") {
+        Ok(list) => list,
+        Err(_) => return Ok(Vec::new()),
+    };
+    let mut cauldrons = Vec::new();
+    for tile_entity in tile_entities.iter_map::<&NbtCompound>() {
+        match CauldronEntity::from_nbt(tile_entity?) {
+            Ok(cauldron) => cauldrons.push(cauldron),
+            Err(CauldronNbtError::NotACauldron(_)) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(cauldrons)
+}
@@ -0,0 +1,44 @@
+/* The C interface to mc-cauldron-brew, see mc-cauldron-capi/src/lib.rs for the details. */
+
+#ifndef MC_CAULDRON_H
+#define MC_CAULDRON_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define MC_CAULDRON_ABI_VERSION 1
+
+typedef struct McCauldronEffect {
+    uint8_t id;
+    uint8_t amplifier;
+    uint32_t duration;
+} McCauldronEffect;
+
+typedef struct McCauldronTable McCauldronTable;
+
+uint32_t mc_cauldron_abi_version(void);
+
+int32_t mc_cauldron_apply_action(uint16_t dv, char letter);
+uint16_t mc_cauldron_dilute(uint16_t dv);
+uint16_t mc_cauldron_apply_wart(uint16_t dv);
+
+uint32_t mc_cauldron_color(uint16_t dv);
+size_t mc_cauldron_effects(uint16_t dv, McCauldronEffect *out, size_t len);
+size_t mc_cauldron_name(uint16_t dv, char *buf, size_t len);
+
+ptrdiff_t mc_cauldron_solve(uint16_t start, uint16_t target, char *buf, size_t len);
+
+McCauldronTable *mc_cauldron_table_new(void);
+void mc_cauldron_table_free(McCauldronTable *table);
+ptrdiff_t mc_cauldron_table_recipe(const McCauldronTable *table, uint16_t target, char *buf,
+                                  size_t len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
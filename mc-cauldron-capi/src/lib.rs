@@ -0,0 +1,223 @@
+//! A C interface to the brewing logic, for mods and launchers that aren't written in Rust.
+//!
+//! Liquids are passed as their damage value and actions as their letter, e.g. `'E'` for a spider
+//! eye. Functions that write strings follow `snprintf`: they return the length of the whole
+//! string and only write it, with a terminating NUL, if the buffer is large enough.
+//!
+//! The declarations are in `include/mc_cauldron.h`. Functions are only ever added, so a program
+//! built against one `MC_CAULDRON_ABI_VERSION` works with every later library.
+
+use mc_cauldron_brew::solver::{self, RecipeTable};
+use mc_cauldron_brew::{Action, LiquidData, Recipe};
+use std::os::raw::c_char;
+use std::{ptr, slice};
+
+/// The version of this interface, see `mc_cauldron_abi_version`.
+pub const ABI_VERSION: u32 = 1;
+
+/// An effect of a potion.
+#[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct McCauldronEffect {
+    /// The numeric id the game uses for the effect.
+    pub id: u8,
+    /// 0 for the first tier, 1 for the second and so on.
+    pub amplifier: u8,
+    /// The duration in ticks, 1 for instant effects.
+    pub duration: u32,
+}
+
+/// The version of this interface, so that programs can check they got a library that has every
+/// function they need.
+#[no_mangle]
+pub extern "C" fn mc_cauldron_abi_version() -> u32 {
+    ABI_VERSION
+}
+
+/// Calculates the result of taking the action with this letter, or -1 if there is no such action.
+#[no_mangle]
+pub extern "C" fn mc_cauldron_apply_action(dv: u16, letter: c_char) -> i32 {
+    match Action::from_letter(letter as u8 as char) {
+        Some(action) => action.apply_to(LiquidData(dv)).0 as i32,
+        None => -1,
+    }
+}
+
+/// Calculates the result of adding a water bucket.
+#[no_mangle]
+pub extern "C" fn mc_cauldron_dilute(dv: u16) -> u16 {
+    LiquidData(dv).dilute().0
+}
+
+/// Calculates the result of adding a nether wart.
+#[no_mangle]
+pub extern "C" fn mc_cauldron_apply_wart(dv: u16) -> u16 {
+    LiquidData(dv).apply_wart().0
+}
+
+/// The color of the liquid as 0xRRGGBB.
+#[no_mangle]
+pub extern "C" fn mc_cauldron_color(dv: u16) -> u32 {
+    LiquidData(dv).color()
+}
+
+/// Writes up to `len` effects of a potion to `out` and returns how many it has.
+///
+/// # Safety
+///
+/// `out` must point to `len` writable effects, or be null if `len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn mc_cauldron_effects(
+    dv: u16,
+    out: *mut McCauldronEffect,
+    len: usize,
+) -> usize {
+    let effects = LiquidData(dv).effect_details();
+    for (i, details) in effects.iter().take(len).enumerate() {
+        *out.add(i) = McCauldronEffect {
+            id: details.effect.id(),
+            amplifier: details.amplifier,
+            duration: details.duration,
+        };
+    }
+    effects.len()
+}
+
+/// Writes the display name of a potion to `buf`, see the module docs.
+///
+/// # Safety
+///
+/// `buf` must point to `len` writable bytes, or be null if `len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn mc_cauldron_name(dv: u16, buf: *mut c_char, len: usize) -> usize {
+    write_string(&LiquidData(dv).name(), buf, len)
+}
+
+/// Writes the shortest recipe from `start` to `target` to `buf`, see the module docs.
+///
+/// Returns -1 if `target` can't be brewed from `start`.
+///
+/// # Safety
+///
+/// `buf` must point to `len` writable bytes, or be null if `len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn mc_cauldron_solve(
+    start: u16,
+    target: u16,
+    buf: *mut c_char,
+    len: usize,
+) -> isize {
+    write_recipe(
+        solver::breadth_first_from(LiquidData(start), LiquidData(target)),
+        buf,
+        len,
+    )
+}
+
+/// Builds the table of shortest recipes from water, which makes looking them up instant.
+///
+/// Free it with `mc_cauldron_table_free`.
+#[no_mangle]
+pub extern "C" fn mc_cauldron_table_new() -> *mut RecipeTable {
+    Box::into_raw(Box::new(RecipeTable::build()))
+}
+
+/// Frees a table from `mc_cauldron_table_new`. Does nothing for null.
+///
+/// # Safety
+///
+/// `table` must come from `mc_cauldron_table_new` and not have been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn mc_cauldron_table_free(table: *mut RecipeTable) {
+    if !table.is_null() {
+        drop(Box::from_raw(table));
+    }
+}
+
+/// Writes the shortest recipe for `target` from the table to `buf`, like `mc_cauldron_solve`.
+///
+/// # Safety
+///
+/// `table` must come from `mc_cauldron_table_new`, and `buf` must point to `len` writable bytes,
+/// or be null if `len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn mc_cauldron_table_recipe(
+    table: *const RecipeTable,
+    target: u16,
+    buf: *mut c_char,
+    len: usize,
+) -> isize {
+    write_recipe((*table).recipe(LiquidData(target)), buf, len)
+}
+
+unsafe fn write_recipe(recipe: Option<Recipe>, buf: *mut c_char, len: usize) -> isize {
+    match recipe {
+        Some(recipe) => write_string(&recipe.to_string(), buf, len) as isize,
+        None => -1,
+    }
+}
+
+/// Copies `s` and a NUL to `buf` if they fit and returns the length of `s`.
+unsafe fn write_string(s: &str, buf: *mut c_char, len: usize) -> usize {
+    if s.len() < len {
+        let out = slice::from_raw_parts_mut(buf as *mut u8, len);
+        out[..s.len()].copy_from_slice(s.as_bytes());
+        out[s.len()] = 0;
+    } else if len > 0 {
+        ptr::write(buf, 0);
+    }
+    s.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    #[test]
+    fn actions_match_the_crate() {
+        assert_eq!(mc_cauldron_apply_action(0, b'F' as c_char), 16896);
+        assert_eq!(mc_cauldron_apply_action(0, b'X' as c_char), -1);
+        assert_eq!(mc_cauldron_apply_wart(31011), 16675);
+    }
+
+    #[test]
+    fn strings_are_written_if_they_fit() {
+        let mut buf = [0x7f as c_char; 8];
+        unsafe {
+            assert_eq!(mc_cauldron_solve(0, 16896, buf.as_mut_ptr(), buf.len()), 1);
+            assert_eq!(CStr::from_ptr(buf.as_ptr()).to_str(), Ok("F"));
+            let name_len = mc_cauldron_name(16896, buf.as_mut_ptr(), buf.len());
+            assert_eq!(name_len, "Potion of Dullness".len());
+            assert_eq!(buf[0], 0);
+            assert_eq!(mc_cauldron_name(16896, ptr::null_mut(), 0), name_len);
+        }
+    }
+
+    #[test]
+    fn effects_are_counted_even_if_they_dont_fit() {
+        let mut out = [McCauldronEffect::default(); 1];
+        let count = unsafe { mc_cauldron_effects(16896, out.as_mut_ptr(), out.len()) };
+        assert_eq!(count, 2);
+        assert_eq!(
+            out[0],
+            McCauldronEffect {
+                id: 4,
+                amplifier: 0,
+                duration: 3600
+            }
+        );
+    }
+
+    #[test]
+    fn tables_look_up_recipes() {
+        let mut buf = [0 as c_char; 32];
+        unsafe {
+            let table = mc_cauldron_table_new();
+            let length = mc_cauldron_table_recipe(table, 25486, buf.as_mut_ptr(), buf.len());
+            assert_eq!(CStr::from_ptr(buf.as_ptr()).to_str(), Ok("SECNSN"));
+            assert_eq!(length, 6);
+            mc_cauldron_table_free(table);
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{Json, Router};
+use clap::Parser;
+use mc_cauldron_brew::solver::{self, RecipeTable};
+use mc_cauldron_brew::{EffectMask, LiquidData, Recipe};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::error::Error;
+use std::fs;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Answers questions about potions over HTTP, with JSON.
+///
+/// GET /potion/{dv} describes a liquid: its name, color, effects and shortest recipe from water.
+/// GET /solve?effects=strength,poison&without=slowness finds the shortest recipe for a potion
+/// with all of `effects` and none of `without`.
+#[derive(Parser)]
+struct Args {
+    /// Where to listen.
+    #[arg(long, default_value = "127.0.0.1:3000")]
+    addr: SocketAddr,
+    /// Load the recipes from a table written by "table --format binary" instead of searching.
+    #[arg(long, value_name = "FILE")]
+    table: Option<PathBuf>,
+}
+
+/// The shortest recipe from water for every liquid, indexed by damage value.
+struct Recipes(Vec<Option<Recipe>>);
+
+impl Recipes {
+    fn load(table: Option<&std::path::Path>) -> Result<Self, Box<dyn Error>> {
+        let recipes = match table {
+            Some(path) => {
+                let bytes = fs::read(path)?;
+                if !solver::is_lookup_table(&bytes) {
+                    return Err(format!("{} is not a lookup table", path.display()).into());
+                }
+                LiquidData::all()
+                    .map(|ld| solver::lookup(&bytes, ld))
+                    .collect()
+            }
+            None => {
+                let table = RecipeTable::build();
+                LiquidData::all().map(|ld| table.recipe(ld)).collect()
+            }
+        };
+        Ok(Recipes(recipes))
+    }
+
+    fn get(&self, ld: LiquidData) -> Option<&Recipe> {
+        self.0.get(ld.0 as usize)?.as_ref()
+    }
+}
+
+type HttpError = (StatusCode, String);
+
+fn describe(ld: LiquidData, recipe: Option<&Recipe>) -> Value {
+    let effects: Vec<Value> = ld
+        .effect_details()
+        .iter()
+        .map(|details| {
+            json!({
+                "effect": details.effect.name(),
+                "amplifier": details.amplifier,
+                "duration": details.duration,
+                "text": details.to_string(),
+            })
+        })
+        .collect();
+    json!({
+        "dv": ld.0,
+        "name": ld.name(),
+        "color": format!("#{:06x}", ld.color()),
+        "effects": effects,
+        "recipe": recipe.map(|r| r.to_string()),
+    })
+}
+
+async fn potion(State(recipes): State<Arc<Recipes>>, Path(dv): Path<u16>) -> Json<Value> {
+    let ld = LiquidData(dv);
+    Json(describe(ld, recipes.get(ld)))
+}
+
+#[derive(Deserialize)]
+struct SolveQuery {
+    /// Comma-separated effects the potion must have.
+    effects: Option<String>,
+    /// Comma-separated effects the potion must not have.
+    without: Option<String>,
+}
+
+fn parse_effects(list: Option<&str>) -> Result<EffectMask, HttpError> {
+    list.unwrap_or("")
+        .split(',')
+        .filter(|name| !name.trim().is_empty())
+        .map(|name| {
+            name.trim()
+                .parse()
+                .map_err(|e| (StatusCode::BAD_REQUEST, format!("{}", e)))
+        })
+        .collect()
+}
+
+/// Picks the shortest recipe, then the first in recipe order, which is what searching for the
+/// effects would find.
+async fn solve(
+    State(recipes): State<Arc<Recipes>>,
+    Query(query): Query<SolveQuery>,
+) -> Result<Json<Value>, HttpError> {
+    let wanted = parse_effects(query.effects.as_deref())?;
+    let forbidden = parse_effects(query.without.as_deref())?;
+    let found = LiquidData::all()
+        .filter_map(|ld| Some((ld, recipes.get(ld)?)))
+        .filter(|(ld, _)| {
+            let effects = ld.effect_mask();
+            wanted.is_subset(effects) && effects.is_disjoint(forbidden)
+        })
+        .min_by_key(|&(_, recipe)| (recipe.0.len(), recipe));
+    match found {
+        Some((ld, recipe)) => Ok(Json(describe(ld, Some(recipe)))),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            "no potion with these effects can be brewed".to_string(),
+        )),
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse();
+    let recipes = Arc::new(Recipes::load(args.table.as_deref())?);
+    let app = Router::new()
+        .route("/potion/{dv}", get(potion))
+        .route("/solve", get(solve))
+        .with_state(recipes);
+    let listener = tokio::net::TcpListener::bind(args.addr).await?;
+    eprintln!("listening on http://{}", listener.local_addr()?);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{describe, parse_effects, solve, Recipes, SolveQuery};
+    use axum::extract::{Query, State};
+    use axum::http::StatusCode;
+    use mc_cauldron_brew::fixtures::{STRENGTH, UNREACHABLE};
+    use mc_cauldron_brew::{EffectMask, PotionEffect};
+    use serde_json::Value;
+    use std::sync::{Arc, OnceLock};
+
+    /// Searching takes a while, so the tests share one table.
+    fn recipes() -> Arc<Recipes> {
+        static RECIPES: OnceLock<Arc<Recipes>> = OnceLock::new();
+        RECIPES
+            .get_or_init(|| Arc::new(Recipes::load(None).unwrap()))
+            .clone()
+    }
+
+    async fn solve_for(effects: &str, without: &str) -> Result<Value, StatusCode> {
+        let query = SolveQuery {
+            effects: Some(effects.to_string()),
+            without: Some(without.to_string()),
+        };
+        match solve(State(recipes()), Query(query)).await {
+            Ok(json) => Ok(json.0),
+            Err((status, _)) => Err(status),
+        }
+    }
+
+    #[test]
+    fn effects_are_parsed() {
+        assert_eq!(parse_effects(None), Ok(EffectMask::EMPTY));
+        assert_eq!(
+            parse_effects(Some(" strength, ,poison")),
+            Ok(EffectMask::from(
+                &[PotionEffect::Strength, PotionEffect::Poison][..]
+            ))
+        );
+        let (status, _) = parse_effects(Some("strength,flying")).unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn solve_finds_the_shortest_recipe() {
+        let found = solve_for("strength,poison", "").await.unwrap();
+        assert_eq!(found["dv"], STRENGTH.liquid.0);
+        assert_eq!(found["recipe"], STRENGTH.recipe.unwrap());
+
+        // the effects of UNREACHABLE, which no potion that can be brewed combines
+        assert_eq!(
+            solve_for("swiftness,regeneration", "").await,
+            Err(StatusCode::NOT_FOUND)
+        );
+        assert_eq!(solve_for("flying", "").await, Err(StatusCode::BAD_REQUEST));
+    }
+
+    #[test]
+    fn unbrewable_liquids_have_no_recipe() {
+        let recipes = recipes();
+        let described = describe(UNREACHABLE.liquid, recipes.get(UNREACHABLE.liquid));
+        assert_eq!(described["dv"], UNREACHABLE.liquid.0);
+        assert_eq!(described["recipe"], Value::Null);
+        let effects = described["effects"].as_array().unwrap();
+        assert_eq!(effects.len(), UNREACHABLE.effects.len());
+        assert_eq!(effects[0]["effect"], UNREACHABLE.effects[0].name());
+    }
+}
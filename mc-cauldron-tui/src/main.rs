@@ -0,0 +1,151 @@
+//! An interactive cauldron: press the letter of an action and watch the liquid change.
+//!
+//! S, G, E, F, B, C add an ingredient, W adds water and N a nether wart, see `Action::letter`.
+//! Backspace undoes the last action and Y redoes it, R empties the cauldron and Q or Esc quits.
+
+use mc_cauldron_brew::solver::RecipeTable;
+use mc_cauldron_brew::{Action, CauldronSession, Recipe};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::io;
+
+/// The cauldron and the actions that led to it.
+struct App {
+    session: CauldronSession,
+    table: RecipeTable,
+}
+
+impl App {
+    fn new() -> Self {
+        App {
+            session: CauldronSession::default(),
+            table: RecipeTable::build(),
+        }
+    }
+
+    /// Handles a key press and returns whether to quit.
+    fn press(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc => return true,
+            KeyCode::Backspace => {
+                self.session.undo();
+            }
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                self.session.redo();
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => self.session.reset(),
+            KeyCode::Char(c) => {
+                if let Some(action) = Action::from_letter(c.to_ascii_uppercase()) {
+                    self.session.apply(action);
+                }
+            }
+            _ => {}
+        }
+        false
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let ld = self.session.current();
+        let [bits, potion, recipes, help] = Layout::vertical([
+            Constraint::Length(4),
+            Constraint::Min(4),
+            Constraint::Length(4),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        frame.render_widget(
+            Paragraph::new(self.bit_lines())
+                .block(Block::bordered().title(format!(" {:05} = {:#06x} ", ld.0, ld.0))),
+            bits,
+        );
+
+        let color = ld.color();
+        let swatch = Color::Rgb((color >> 16) as u8, (color >> 8) as u8, color as u8);
+        let mut lines = vec![Line::from(vec![
+            Span::styled("██ ", Style::new().fg(swatch)),
+            Span::styled(ld.name(), Style::new().add_modifier(Modifier::BOLD)),
+        ])];
+        let effects = ld.effect_details();
+        if effects.is_empty() {
+            lines.push(Line::from("  no effects"));
+        }
+        for effect in effects {
+            lines.push(Line::from(format!("  {}", effect)));
+        }
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title(" Potion ")),
+            potion,
+        );
+
+        let shortest = match self.table.recipe(ld) {
+            Some(recipe) => format!("shortest from water: {}", show(&recipe)),
+            None => "can't be brewed from water".to_string(),
+        };
+        frame.render_widget(
+            Paragraph::new(vec![
+                Line::from(format!("taken so far: {}", show(&self.session.to_recipe()))),
+                Line::from(shortest),
+            ])
+            .block(Block::bordered().title(" Recipe ")),
+            recipes,
+        );
+
+        frame.render_widget(
+            Paragraph::new(
+                " S G E F B C: ingredients  W: water  N: wart  Backspace: undo  Y: redo  R: reset  Q: quit",
+            )
+            .style(Style::new().add_modifier(Modifier::DIM)),
+            help,
+        );
+    }
+
+    /// The bits from 14 down to 0 with their indices, marking the ones the last action changed.
+    fn bit_lines(&self) -> Vec<Line<'static>> {
+        let ld = self.session.current();
+        let changed = self.session.previous().map_or(0, |prev| prev.0 ^ ld.0);
+        let mut indices = Vec::new();
+        let mut values = Vec::new();
+        for bit in (0..15).rev() {
+            indices.push(Span::raw(format!("{:>3}", bit)));
+            let style = if changed & (1 << bit) != 0 {
+                Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::new()
+            };
+            values.push(Span::styled(format!("{:>3}", (ld.0 >> bit) & 1), style));
+        }
+        vec![Line::from(indices), Line::from(values)]
+    }
+}
+
+fn show(recipe: &Recipe) -> String {
+    if recipe.0.is_empty() {
+        "(nothing)".to_string()
+    } else {
+        recipe.to_string()
+    }
+}
+
+fn run(terminal: &mut DefaultTerminal) -> io::Result<()> {
+    let mut app = App::new();
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && app.press(key.code) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn main() -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal);
+    ratatui::restore();
+    result
+}